@@ -1,5 +1,5 @@
 use reversi::{
-    computer::{PlayerType, WeightedComputer}, error::ReversiError, game::{PlayerManager, SimpleReversiGame}, stone::Stone
+    computer::{PlayerType, WeightedComputer}, game::{PlayerManager, SimpleReversiGame}, outcome::{GameResult, MoveOutcome}, point::{Action, Point}, stone::Stone
 };
 use std::io::stdin;
 
@@ -21,72 +21,54 @@ fn main() {
             }
         );
 
-        let (x, y) = if let Some(point) = player_mgr.decide(game.board(), game.turn()) {
-            (point.x, point.y)
+        let Point { x, y } = if let Some(point) = player_mgr.decide(game.board(), game.turn()) {
+            point
         } else {
-            let Some(xy) = read_input() else {
+            let Some(point) = read_input(game.board().size()) else {
                 continue;
             };
 
-            xy
+            point
         };
 
-        let Err(error) = game.put_stone(x, y) else {
-            continue;
-        };
+        match game.put_stone(x, y) {
+            Ok(MoveOutcome::Continue) => {}
 
-        match error {
-            ReversiError::StoneAlreadyPlaced
-            | ReversiError::IndexOutOfBound
-            | ReversiError::InvalidMove
-            | ReversiError::NoStoneToFlip => {
-                println!("{:?}", error);
+            Ok(MoveOutcome::OpponentPasses) => {
+                println!("{} cannot put stone.", game.turn().opposite());
             }
 
-            ReversiError::NextPlayerCantPutStone(stone) => {
-                println!("{:?}: {} cannot put stone.", error, stone);
-            }
-
-            ReversiError::GameOverWithWinner(winner) => {
-                println!("{} wins!", winner);
+            Ok(MoveOutcome::GameOver { result, score }) => {
+                match result {
+                    GameResult::Winner(winner) => println!("{} wins!", winner),
+                    GameResult::Draw => println!("Draw!"),
+                }
+                println!("{} - {}", score.black, score.white);
                 println!("{}", &game);
                 break;
             }
 
-            ReversiError::GameOverWithDraw => {
-                println!("Draw!");
-                println!("{}", &game);
-                break;
+            Err(error) => {
+                println!("{:?}", error);
             }
         }
     }
 }
 
-fn read_input() -> Option<(usize, usize)> {
+fn read_input(size: usize) -> Option<Point> {
     let mut buff = String::new();
-        stdin().read_line(&mut buff).unwrap();
-        let mut split = buff.trim().split(' ');
-
-        let Some(x) = split.next() else {
-            return None;
-        };
-        let Some(y) = split.next() else {
-            return None;
-        };
+    stdin().read_line(&mut buff).unwrap();
+    let input: String = buff.split_whitespace().collect();
 
-        let Some(x) = x.chars().next() else {
-            return None;
-        };
-        let Ok(y) = y.parse::<usize>() else {
-            return None;
-        };
-        let y = y - 1;
-
-        let x = if x.is_ascii_uppercase() {
-            x as usize - 'A' as usize
-        } else {
-            x as usize - 'a' as usize
-        };
-
-        Some((x, y))
+    match Action::parse_on(&input, size) {
+        Ok(Action::Put(point)) => Some(point),
+        Ok(Action::Pass) => {
+            println!("You can still put a stone.");
+            None
+        }
+        Err(error) => {
+            println!("{:?}", error);
+            None
+        }
+    }
 }
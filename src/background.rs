@@ -0,0 +1,162 @@
+// Running a computer on its own thread.
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+};
+
+use crate::{
+    board::ReversiBoard, computer::Computer, point::Point, position::Position, stone::Stone,
+};
+
+/// Tells a search to stop. Clones share the same flag.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    #[inline]
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    /// The last depth searched to the end. For Monte Carlo tree search, the
+    /// length of the most visited line.
+    pub depth: usize,
+    pub best_move: Point,
+    pub nodes: u64,
+}
+
+/// A computer deciding on a background thread.
+pub struct Thinking {
+    handle: JoinHandle<Point>,
+    token: CancelToken,
+    progress: Receiver<Progress>,
+    latest: Option<Progress>,
+}
+
+impl Thinking {
+    /// Starts `computer` deciding on a copy of `board`, which is a `BitBoard`
+    /// for 8x8 boards.
+    pub fn spawn<C>(computer: Arc<C>, board: &dyn ReversiBoard) -> Self
+    where
+        C: Computer + Send + Sync + ?Sized + 'static,
+    {
+        // `dyn ReversiBoard` isn't `Send`, so the thread gets a position. Its
+        // turn is ignored, as computers only ever play their own color.
+        let position = Position::from_board(board, Stone::Black);
+        let token = CancelToken::new();
+        let (sender, progress) = mpsc::channel();
+
+        let handle = {
+            let token = token.clone();
+            thread::spawn(move || {
+                let board = position.to_fast_board();
+                computer.decide_with(board.as_ref(), &token, &mut |progress| {
+                    let _ = sender.send(progress);
+                })
+            })
+        };
+
+        Self {
+            handle,
+            token,
+            progress,
+            latest: None,
+        }
+    }
+
+    /// Stops the search. `join` then returns the best move found so far.
+    pub fn cancel(&self) {
+        self.token.cancel();
+    }
+
+    #[inline]
+    pub fn token(&self) -> &CancelToken {
+        &self.token
+    }
+
+    #[inline]
+    pub fn is_finished(&self) -> bool {
+        self.handle.is_finished()
+    }
+
+    /// The most recent progress report, without waiting.
+    pub fn progress(&mut self) -> Option<Progress> {
+        if let Some(progress) = self.progress.try_iter().last() {
+            self.latest = Some(progress);
+        }
+
+        self.latest
+    }
+
+    /// Waits for the next progress report. None once the search is over.
+    pub fn wait_progress(&mut self) -> Option<Progress> {
+        let progress = self.progress.recv().ok()?;
+        self.latest = Some(progress);
+
+        Some(progress)
+    }
+
+    /// Waits for the decision.
+    pub fn join(self) -> Point {
+        self.handle.join().expect("computer panicked while deciding")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        computer::AlphaBetaComputer,
+        game::SimpleReversiGame,
+        mcts::{Budget, MctsComputer},
+    };
+
+    use super::*;
+
+    #[test]
+    fn cancel_alpha_beta() {
+        let game = SimpleReversiGame::from_transcript("f5d6c3d3c4f4").unwrap();
+        let computer = Arc::new(AlphaBetaComputer::new(Stone::White, 60));
+
+        let mut thinking = Thinking::spawn(computer, game.board());
+        let first = thinking.wait_progress().unwrap();
+        let second = thinking.wait_progress().unwrap();
+        assert_eq!((first.depth, second.depth), (1, 2));
+        assert!(second.nodes > first.nodes);
+
+        thinking.cancel();
+        assert_eq!(thinking.progress().map(|p| p.depth >= 2), Some(true));
+
+        let decided = thinking.join();
+        assert!(game.board().check_can_put(decided.x, decided.y, Stone::White));
+    }
+
+    #[test]
+    fn cancel_mcts() {
+        let game = SimpleReversiGame::new();
+        let computer = MctsComputer::with_seed(Stone::Black, Budget::Iterations(usize::MAX), 3);
+
+        let mut thinking = Thinking::spawn(Arc::new(computer), game.board());
+        let progress = thinking.wait_progress().unwrap();
+        assert!(progress.depth >= 1);
+
+        thinking.cancel();
+        let decided = thinking.join();
+        assert!(game.board().check_can_put(decided.x, decided.y, Stone::Black));
+    }
+}
@@ -0,0 +1,80 @@
+// Generates a dataset of self-play positions, see `reversi::selfplay`.
+//
+// Usage: selfplay <games> <output> [--depth D] [--random-plies K]
+//                 [--seed S] [--search-depth D]
+
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    process::exit,
+    str::FromStr,
+};
+
+use reversi::{
+    computer::AlphaBetaComputer,
+    selfplay::{Label, SelfPlay},
+    stone::Stone,
+};
+
+const USAGE: &str = "usage: selfplay <games> <output> [--depth D] [--random-plies K] [--seed S] [--search-depth D]";
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let [games, output, options @ ..] = &args[..] else {
+        fail(USAGE);
+    };
+
+    let games: usize = parse(games);
+    let mut depth = 4;
+    let mut random_plies = 8;
+    let mut seed = None;
+    let mut search_depth = None;
+
+    for option in options.chunks(2) {
+        let [name, value] = option else {
+            fail(USAGE);
+        };
+
+        match name.as_str() {
+            "--depth" => depth = parse(value),
+            "--random-plies" => random_plies = parse(value),
+            "--seed" => seed = Some(parse(value)),
+            "--search-depth" => search_depth = Some(parse(value)),
+            _ => fail(USAGE),
+        }
+    }
+
+    let black = Box::new(AlphaBetaComputer::new(Stone::Black, depth));
+    let white = Box::new(AlphaBetaComputer::new(Stone::White, depth));
+    let mut self_play = match seed {
+        Some(seed) => SelfPlay::with_seed(black, white, seed),
+        None => SelfPlay::new(black, white),
+    };
+    self_play.set_random_plies(random_plies);
+    println!("seed {}", self_play.seed());
+    if let Some(depth) = search_depth {
+        let search = AlphaBetaComputer::new(Stone::Black, depth);
+        self_play.set_label(Label::Evaluation(Box::new(search)));
+    }
+
+    let file = File::create(output).unwrap_or_else(|error| fail(&error.to_string()));
+    let mut writer = BufWriter::new(file);
+    let written = self_play
+        .play(games, &mut writer)
+        .unwrap_or_else(|error| fail(&format!("{:?}", error)));
+    writer
+        .flush()
+        .unwrap_or_else(|error| fail(&error.to_string()));
+    println!("{} positions from {} games", written, games);
+}
+
+fn parse<T: FromStr>(value: &str) -> T {
+    value
+        .parse()
+        .unwrap_or_else(|_| fail(&format!("invalid number: {}", value)))
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("{}", message);
+    exit(1);
+}
@@ -0,0 +1,437 @@
+use std::cell::{Cell, OnceCell};
+
+use crate::{
+    board::{check_next_turn, Board, ReversiBoard, DEFAULT_BOARD_SIZE},
+    error::ReversiError,
+    game::Result,
+    outcome::{GameResult, MoveOutcome},
+    point::Point,
+    stone::Stone,
+    zobrist,
+};
+
+const FILE_A: u64 = 0x0101_0101_0101_0101;
+const FILE_H: u64 = 0x8080_8080_8080_8080;
+
+// The four lines through a square, as the shift of one step along the line
+// and the squares that a step left or right can land on without wrapping
+// around to the other side of the board.
+pub(crate) const LINES: [(u32, u64, u64); 4] = [
+    (1, !FILE_A, !FILE_H),
+    (8, !0, !0),
+    (9, !FILE_A, !FILE_H),
+    (7, !FILE_H, !FILE_A),
+];
+
+/// An 8x8 board stored as one `u64` mask per color.
+///
+/// `board()` and `board_mut()` are served from a lazily built `Board`, so
+/// code that only uses the other methods never allocates.
+#[derive(Clone)]
+pub struct BitBoard {
+    black: Cell<u64>,
+    white: Cell<u64>,
+    cells: OnceCell<Board>,
+    cells_modified: Cell<bool>,
+    hash: Cell<u64>,
+}
+
+impl Default for BitBoard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BitBoard {
+    pub fn new() -> Self {
+        Self {
+            black: Cell::new(0),
+            white: Cell::new(0),
+            cells: OnceCell::new(),
+            cells_modified: Cell::new(false),
+            hash: Cell::new(0),
+        }
+    }
+
+    pub fn black(&self) -> u64 {
+        self.sync();
+        self.black.get()
+    }
+
+    pub fn white(&self) -> u64 {
+        self.sync();
+        self.white.get()
+    }
+
+    pub fn mask(&self, player: Stone) -> u64 {
+        match player {
+            Stone::Black => self.black(),
+            Stone::White => self.white(),
+        }
+    }
+
+    pub fn legal_moves(&self, player: Stone) -> u64 {
+        moves_of(self.mask(player), self.mask(player.opposite()))
+    }
+
+    pub fn flippable(&self, x: usize, y: usize, player: Stone) -> u64 {
+        if !self.in_range(x, y) {
+            return 0;
+        }
+
+        flips_of(self.mask(player), self.mask(player.opposite()), bit(x, y))
+    }
+
+    // Rebuilds the masks after the cells were handed out through `board_mut`.
+    fn sync(&self) {
+        if !self.cells_modified.get() {
+            return;
+        }
+
+        let cells = self.cells.get().expect("cells must exist when modified");
+        assert_eq!(cells.len(), DEFAULT_BOARD_SIZE, "BitBoard only supports 8x8 boards");
+
+        let (mut black, mut white, mut hash) = (0, 0, 0);
+        for (y, row) in cells.iter().enumerate() {
+            assert_eq!(row.len(), DEFAULT_BOARD_SIZE, "BitBoard only supports 8x8 boards");
+            for (x, &cell) in row.iter().enumerate() {
+                let Some(stone) = cell else {
+                    continue;
+                };
+
+                match stone {
+                    Stone::Black => black |= bit(x, y),
+                    Stone::White => white |= bit(x, y),
+                }
+                hash ^= zobrist::square_key(x, y, stone);
+            }
+        }
+
+        self.black.set(black);
+        self.white.set(white);
+        self.hash.set(hash);
+        self.cells_modified.set(false);
+    }
+
+    fn build_cells(&self) -> Board {
+        let mut cells = vec![vec![None; DEFAULT_BOARD_SIZE]; DEFAULT_BOARD_SIZE];
+        for (y, row) in cells.iter_mut().enumerate() {
+            for (x, cell) in row.iter_mut().enumerate() {
+                *cell = self.get_at(x, y);
+            }
+        }
+
+        cells
+    }
+
+    // Must be called before the masks are changed, so that the cached cells
+    // are not served stale.
+    fn begin_update(&mut self) {
+        self.sync();
+        self.cells = OnceCell::new();
+    }
+
+    fn set_masks(&mut self, black: u64, white: u64) {
+        let mut hash = self.hash.get();
+        let changed = (self.black.get() ^ black) | (self.white.get() ^ white);
+        let mut squares = changed;
+        while squares != 0 {
+            let index = squares.trailing_zeros() as usize;
+            let (x, y) = (index % DEFAULT_BOARD_SIZE, index / DEFAULT_BOARD_SIZE);
+            let square = 1 << index;
+
+            if self.black.get() & square != 0 {
+                hash ^= zobrist::square_key(x, y, Stone::Black);
+            }
+            if self.white.get() & square != 0 {
+                hash ^= zobrist::square_key(x, y, Stone::White);
+            }
+            if black & square != 0 {
+                hash ^= zobrist::square_key(x, y, Stone::Black);
+            }
+            if white & square != 0 {
+                hash ^= zobrist::square_key(x, y, Stone::White);
+            }
+
+            squares &= squares - 1;
+        }
+
+        self.black.set(black);
+        self.white.set(white);
+        self.hash.set(hash);
+    }
+}
+
+#[inline]
+fn bit(x: usize, y: usize) -> u64 {
+    1 << (y * DEFAULT_BOARD_SIZE + x)
+}
+
+/// The legal moves of the player owning `own`, as a mask.
+#[inline]
+pub fn moves_of(own: u64, opponent: u64) -> u64 {
+    let empty = !(own | opponent);
+
+    let mut moves = 0;
+    for (shift, left_mask, right_mask) in LINES {
+        let mut left = opponent & (own << shift) & left_mask;
+        let mut right = opponent & (own >> shift) & right_mask;
+        for _ in 0..5 {
+            left |= opponent & (left << shift) & left_mask;
+            right |= opponent & (right >> shift) & right_mask;
+        }
+        moves |= (left << shift) & left_mask | (right >> shift) & right_mask;
+    }
+
+    moves & empty
+}
+
+/// The discs flipped by the player owning `own` playing on the single bit of
+/// `square`, or 0 if the square is taken.
+#[inline]
+pub fn flips_of(own: u64, opponent: u64, square: u64) -> u64 {
+    if (own | opponent) & square != 0 {
+        return 0;
+    }
+
+    let mut result = 0;
+    for (shift, left_mask, right_mask) in LINES {
+        let mut line = 0;
+        let mut cursor = (square << shift) & left_mask;
+        while cursor & opponent != 0 {
+            line |= cursor;
+            cursor = (cursor << shift) & left_mask;
+        }
+        if cursor & own != 0 {
+            result |= line;
+        }
+
+        let mut line = 0;
+        let mut cursor = (square >> shift) & right_mask;
+        while cursor & opponent != 0 {
+            line |= cursor;
+            cursor = (cursor >> shift) & right_mask;
+        }
+        if cursor & own != 0 {
+            result |= line;
+        }
+    }
+
+    result
+}
+
+impl ReversiBoard for BitBoard {
+    #[inline]
+    fn size(&self) -> usize {
+        DEFAULT_BOARD_SIZE
+    }
+
+    fn board(&self) -> &Board {
+        self.sync();
+        self.cells.get_or_init(|| self.build_cells())
+    }
+
+    fn board_mut(&mut self) -> &mut Board {
+        self.sync();
+        self.cells.get_or_init(|| self.build_cells());
+        self.cells_modified.set(true);
+        self.cells.get_mut().unwrap()
+    }
+
+    fn get_at(&self, x: usize, y: usize) -> Option<Stone> {
+        if !self.in_range(x, y) {
+            return None;
+        }
+
+        let square = bit(x, y);
+        if self.black() & square != 0 {
+            Some(Stone::Black)
+        } else if self.white() & square != 0 {
+            Some(Stone::White)
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn in_range(&self, x: usize, y: usize) -> bool {
+        x < DEFAULT_BOARD_SIZE && y < DEFAULT_BOARD_SIZE
+    }
+
+    fn count(&self, player: Stone) -> usize {
+        self.mask(player).count_ones() as usize
+    }
+
+    fn count_flippable(&self, x: usize, y: usize, player: Stone) -> usize {
+        self.flippable(x, y, player).count_ones() as usize
+    }
+
+    fn is_game_over(&self) -> bool {
+        self.black() | self.white() == u64::MAX
+    }
+
+    fn init_four_central_squares(&mut self) {
+        self.begin_update();
+
+        let half = DEFAULT_BOARD_SIZE / 2;
+        let black = bit(half, half - 1) | bit(half - 1, half);
+        let white = bit(half - 1, half - 1) | bit(half, half);
+        let center = black | white;
+        self.set_masks(
+            (self.black.get() & !center) | black,
+            (self.white.get() & !center) | white,
+        );
+    }
+
+    fn set_at(&mut self, x: usize, y: usize, stone: Option<Stone>) -> Result<()> {
+        if !self.in_range(x, y) {
+            return Err(ReversiError::IndexOutOfBound);
+        }
+
+        self.begin_update();
+        let square = bit(x, y);
+        let (mut black, mut white) = (self.black.get() & !square, self.white.get() & !square);
+        match stone {
+            Some(Stone::Black) => black |= square,
+            Some(Stone::White) => white |= square,
+            None => {}
+        }
+        self.set_masks(black, white);
+
+        Ok(())
+    }
+
+    fn flip(&mut self, x: usize, y: usize) -> Result<()> {
+        if !self.in_range(x, y) {
+            return Err(ReversiError::IndexOutOfBound);
+        }
+
+        if self.get_at(x, y).is_none() {
+            return Err(ReversiError::NoStoneToFlip);
+        }
+
+        self.begin_update();
+        let square = bit(x, y);
+        self.set_masks(self.black.get() ^ square, self.white.get() ^ square);
+
+        Ok(())
+    }
+
+    fn put_stone(&mut self, x: usize, y: usize, player: Stone) -> Result<MoveOutcome> {
+        if !self.check_can_put(x, y, player) {
+            return Err(ReversiError::InvalidMove);
+        }
+
+        let flipped = self.flippable(x, y, player);
+        let placed = bit(x, y) | flipped;
+
+        self.begin_update();
+        let (black, white) = match player {
+            Stone::Black => (self.black.get() | placed, self.white.get() & !flipped),
+            Stone::White => (self.black.get() & !flipped, self.white.get() | placed),
+        };
+        self.set_masks(black, white);
+
+        Ok(check_next_turn(self, player))
+    }
+
+    fn winner(&self) -> GameResult {
+        self.score().result()
+    }
+
+    fn check_can_put(&self, x: usize, y: usize, player: Stone) -> bool {
+        self.in_range(x, y) && self.legal_moves(player) & bit(x, y) != 0
+    }
+
+    fn get_can_put_stones(&self, player: Stone) -> Vec<Point> {
+        let mut moves = self.legal_moves(player);
+        let mut result: Vec<Point> = Vec::with_capacity(moves.count_ones() as usize);
+
+        while moves != 0 {
+            let index = moves.trailing_zeros() as usize;
+            result.push(Point::new(
+                index % DEFAULT_BOARD_SIZE,
+                index / DEFAULT_BOARD_SIZE,
+            ));
+            moves &= moves - 1;
+        }
+
+        result
+    }
+
+    fn hash(&self) -> u64 {
+        self.sync();
+        self.hash.get()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::ArrayBasedBoard;
+
+    use super::*;
+
+    #[test]
+    fn count() {
+        let mut board = BitBoard::new();
+        board.init_four_central_squares();
+        assert_eq!(board.count(Stone::Black), 2);
+        assert_eq!(board.count(Stone::White), 2);
+    }
+
+    #[test]
+    fn init_board() {
+        let mut board = BitBoard::new();
+        board.init_four_central_squares();
+
+        assert_eq!(
+            format!("{:?}", &board as &dyn ReversiBoard),
+            "[][][][][][][][]\n[][][][][][][][]\n[][][][][][][][]\n[][][]⚪︎⚫︎[][][]\n[][][]⚫︎⚪︎[][][]\n[][][][][][][][]\n[][][][][][][][]\n[][][][][][][][]\n"
+        );
+    }
+
+    #[test]
+    fn board_mut_updates_masks() {
+        let mut board = BitBoard::new();
+        board.init_four_central_squares();
+        board.board_mut()[0][0] = Some(Stone::Black);
+
+        assert_eq!(board.get_at(0, 0), Some(Stone::Black));
+        assert_eq!(board.count(Stone::Black), 3);
+
+        board.put_stone(3, 2, Stone::Black).unwrap();
+        assert_eq!(board.board()[2][3], Some(Stone::Black));
+        assert_eq!(board.board()[3][3], Some(Stone::Black));
+    }
+
+    #[test]
+    fn same_as_array_based_board() {
+        let mut array: Box<dyn ReversiBoard> = Box::new(ArrayBasedBoard::new());
+        let mut bits: Box<dyn ReversiBoard> = Box::new(BitBoard::new());
+        array.init_four_central_squares();
+        bits.init_four_central_squares();
+
+        let mut player = Stone::Black;
+        for ply in 0.. {
+            let moves = array.get_can_put_stones(player);
+            assert_eq!(moves, bits.get_can_put_stones(player));
+            for p in &moves {
+                assert_eq!(
+                    array.count_flippable(p.x, p.y, player),
+                    bits.count_flippable(p.x, p.y, player)
+                );
+            }
+
+            let Point { x, y } = moves[(ply * 7) % moves.len()];
+            let result = array.put_stone(x, y, player);
+            assert_eq!(result, bits.put_stone(x, y, player));
+            assert_eq!(array.board(), bits.board());
+
+            match result.unwrap() {
+                MoveOutcome::Continue => player = player.opposite(),
+                MoveOutcome::OpponentPasses => {}
+                MoveOutcome::GameOver { .. } => break,
+            }
+        }
+    }
+}
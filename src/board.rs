@@ -5,9 +5,23 @@ use std::{
 
 use dyn_clone::DynClone;
 
-use crate::{error::ReversiError, game::Result, point::Point, stone::Stone};
+use crate::{
+    error::ReversiError,
+    game::Result,
+    outcome::{GameResult, MoveOutcome, Score},
+    point::Point,
+    stone::Stone,
+    zobrist,
+};
 
 pub const DEFAULT_BOARD_SIZE: usize = 8;
+/// Columns are written as single letters, a to z.
+pub const MAX_BOARD_SIZE: usize = 26;
+
+// Boards are square, of an even size from 4 to `MAX_BOARD_SIZE`.
+pub(crate) fn is_supported_size(size: usize) -> bool {
+    size & 1 == 0 && (4..=MAX_BOARD_SIZE).contains(&size)
+}
 
 pub type Board = Vec<Vec<Option<Stone>>>;
 
@@ -34,11 +48,38 @@ pub trait ReversiBoard: DynClone {
     fn is_game_over(&self) -> bool;
 
     fn init_four_central_squares(&mut self);
+
+    /// Sets up the four central squares, then gives `receiver` the first
+    /// `corners` of a1, h8, h1 and a8, as in a traditional handicap game.
+    /// Fails with `IndexOutOfBound` for more than four corners.
+    fn init_with_handicap(&mut self, corners: usize, receiver: Stone) -> Result<()> {
+        let n = self.size() - 1;
+        let all = [(0, 0), (n, n), (n, 0), (0, n)];
+        if corners > all.len() {
+            return Err(ReversiError::IndexOutOfBound);
+        }
+
+        self.init_four_central_squares();
+        for (x, y) in all.into_iter().take(corners) {
+            self.set_at(x, y, Some(receiver))?;
+        }
+
+        Ok(())
+    }
+
+    fn set_at(&mut self, x: usize, y: usize, stone: Option<Stone>) -> Result<()>;
     fn flip(&mut self, x: usize, y: usize) -> Result<()>;
-    fn put_stone(&mut self, x: usize, y: usize, player: Stone) -> Result<()>;
-    fn winner(&self) -> Result<()>;
+    fn put_stone(&mut self, x: usize, y: usize, player: Stone) -> Result<MoveOutcome>;
+    fn winner(&self) -> GameResult;
     fn check_can_put(&self, x: usize, y: usize, player: Stone) -> bool;
     fn get_can_put_stones(&self, player: Stone) -> Vec<Point>;
+
+    /// Zobrist hash of the stones on the board, see `zobrist`.
+    fn hash(&self) -> u64;
+
+    fn score(&self) -> Score {
+        Score::new(self.count(Stone::Black), self.count(Stone::White))
+    }
 }
 
 impl Debug for dyn ReversiBoard {
@@ -83,9 +124,15 @@ impl Display for dyn ReversiBoard {
     }
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 #[derive(Clone)]
 pub struct ArrayBasedBoard {
     board: Board,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    hash: u64,
+    // Set by `board_mut`, until the next change computes the hash again.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    hash_stale: bool,
 }
 
 impl Default for ArrayBasedBoard {
@@ -100,14 +147,55 @@ impl ArrayBasedBoard {
     }
 
     pub fn with_size(size: usize) -> Self {
-        if size & 1 != 0 || size < 4 {
-            panic!("Board size must be even");
+        if !is_supported_size(size) {
+            panic!("Board size must be even and at most {}", MAX_BOARD_SIZE);
         }
 
         Self {
             board: vec![vec![None; size]; size],
+            hash: 0,
+            hash_stale: false,
         }
     }
+
+    fn set_cell(&mut self, x: usize, y: usize, stone: Option<Stone>) {
+        if self.hash_stale {
+            self.hash = zobrist::hash(self);
+            self.hash_stale = false;
+        }
+        if let Some(old) = self.board[y][x] {
+            self.hash ^= zobrist::square_key(x, y, old);
+        }
+        if let Some(new) = stone {
+            self.hash ^= zobrist::square_key(x, y, new);
+        }
+
+        self.board[y][x] = stone;
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for ArrayBasedBoard {
+    fn deserialize<D>(deserializer: D) -> std::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        #[derive(serde::Deserialize)]
+        struct Fields {
+            board: Board,
+        }
+
+        let Fields { board } = Fields::deserialize(deserializer)?;
+        let size = board.len();
+        if !is_supported_size(size) || board.iter().any(|row| row.len() != size) {
+            return Err(serde::de::Error::custom("invalid board size"));
+        }
+
+        let mut result = Self::with_size(size);
+        *result.board_mut() = board;
+
+        Ok(result)
+    }
 }
 
 impl ReversiBoard for ArrayBasedBoard {
@@ -120,10 +208,10 @@ impl ReversiBoard for ArrayBasedBoard {
         let size = self.board.len();
         let half = size / 2;
 
-        self.board[half - 1][half - 1] = Some(Stone::White);
-        self.board[half - 1][half] = Some(Stone::Black);
-        self.board[half][half - 1] = Some(Stone::Black);
-        self.board[half][half] = Some(Stone::White);
+        self.set_cell(half - 1, half - 1, Some(Stone::White));
+        self.set_cell(half, half - 1, Some(Stone::Black));
+        self.set_cell(half - 1, half, Some(Stone::Black));
+        self.set_cell(half, half, Some(Stone::White));
     }
 
     #[inline]
@@ -133,6 +221,7 @@ impl ReversiBoard for ArrayBasedBoard {
 
     #[inline]
     fn board_mut(&mut self) -> &mut Board {
+        self.hash_stale = true;
         &mut self.board
     }
 
@@ -159,6 +248,16 @@ impl ReversiBoard for ArrayBasedBoard {
             .count()
     }
 
+    fn set_at(&mut self, x: usize, y: usize, stone: Option<Stone>) -> Result<()> {
+        if !self.in_range(x, y) {
+            return Err(ReversiError::IndexOutOfBound);
+        }
+
+        self.set_cell(x, y, stone);
+
+        Ok(())
+    }
+
     fn flip(&mut self, x: usize, y: usize) -> Result<()> {
         if !self.in_range(x, y) {
             return Err(ReversiError::IndexOutOfBound);
@@ -168,7 +267,7 @@ impl ReversiBoard for ArrayBasedBoard {
             return Err(ReversiError::NoStoneToFlip);
         };
 
-        self.board[y][x] = Some(player.opposite());
+        self.set_cell(x, y, Some(player.opposite()));
 
         Ok(())
     }
@@ -178,7 +277,7 @@ impl ReversiBoard for ArrayBasedBoard {
         self.count(Stone::Black) + self.count(Stone::White) == cells_count
     }
 
-    fn put_stone(&mut self, x: usize, y: usize, player: Stone) -> Result<()> {
+    fn put_stone(&mut self, x: usize, y: usize, player: Stone) -> Result<MoveOutcome> {
         if !self.check_can_put(x, y, player) {
             return Err(ReversiError::InvalidMove);
         }
@@ -189,7 +288,7 @@ impl ReversiBoard for ArrayBasedBoard {
         } else if self.board[y][x].is_some() {
             return Err(ReversiError::StoneAlreadyPlaced);
         }
-        self.board[y][x] = Some(player);
+        self.set_cell(x, y, Some(player));
 
         get_flippable(self, x, y, player)
             .iter()
@@ -197,40 +296,11 @@ impl ReversiBoard for ArrayBasedBoard {
                 self.flip(x, y).unwrap();
             });
 
-        if self.is_game_over() {
-            self.winner()?;
-        }
-
-        // self.set_turn(self.turn().opposite());
-
-        if self.get_can_put_stones(player.opposite()).is_empty() {
-            // Next player cannot place stones
-
-            // self.set_turn(self.turn().opposite());
-
-            if self.get_can_put_stones(player).is_empty() {
-                // Both players cannot place stones
-                return self.winner();
-            }
-
-            // Next next player(the player who called this function) can place stones
-            return Err(ReversiError::NextPlayerCantPutStone(player.opposite()));
-        }
-
-        if self.count(player.opposite()) == 0 {
-            // There are no next player's stones
-            return Err(ReversiError::GameOverWithWinner(player));
-        }
-
-        Ok(())
+        Ok(check_next_turn(self, player))
     }
 
-    fn winner(&self) -> Result<()> {
-        match (self.count(Stone::Black), self.count(Stone::White)) {
-            (black, white) if black > white => Err(ReversiError::GameOverWithWinner(Stone::Black)),
-            (black, white) if black < white => Err(ReversiError::GameOverWithWinner(Stone::White)),
-            _ => Err(ReversiError::GameOverWithDraw),
-        }
+    fn winner(&self) -> GameResult {
+        self.score().result()
     }
 
     fn check_can_put(&self, x: usize, y: usize, player: Stone) -> bool {
@@ -287,6 +357,47 @@ impl ReversiBoard for ArrayBasedBoard {
 
         get_flippable(self, x, y, player).len()
     }
+
+    fn hash(&self) -> u64 {
+        match self.hash_stale {
+            true => zobrist::hash(self),
+            false => self.hash,
+        }
+    }
+}
+
+pub(crate) fn check_next_turn(board: &dyn ReversiBoard, player: Stone) -> MoveOutcome {
+    if board.is_game_over() {
+        return game_over(board);
+    }
+
+    if board.get_can_put_stones(player.opposite()).is_empty() {
+        // Next player cannot place stones
+
+        if board.get_can_put_stones(player).is_empty() {
+            // Both players cannot place stones
+            return game_over(board);
+        }
+
+        // Next next player(the player who called this function) can place stones
+        return MoveOutcome::OpponentPasses;
+    }
+
+    if board.count(player.opposite()) == 0 {
+        // There are no next player's stones
+        return game_over(board);
+    }
+
+    MoveOutcome::Continue
+}
+
+fn game_over(board: &dyn ReversiBoard) -> MoveOutcome {
+    let score = board.score();
+
+    MoveOutcome::GameOver {
+        result: score.result(),
+        score,
+    }
 }
 
 fn get_flippable(board: &dyn ReversiBoard, x: usize, y: usize, player: Stone) -> Vec<Point> {
@@ -340,4 +451,49 @@ mod tests {
             "[][][][][][][][]\n[][][][][][][][]\n[][][][][][][][]\n[][][]⚪︎⚫︎[][][]\n[][][]⚫︎⚪︎[][][]\n[][][][][][][][]\n[][][][][][][][]\n[][][][][][][][]\n"
         );
     }
+
+    #[test]
+    fn send_and_sync() {
+        fn check<T: Send + Sync>() {}
+        check::<ArrayBasedBoard>();
+    }
+
+    #[test]
+    fn handicap() {
+        let mut board = ArrayBasedBoard::new();
+        board.init_with_handicap(3, Stone::White).unwrap();
+
+        assert_eq!(board.count(Stone::White), 5);
+        assert_eq!(board.get_at(0, 0), Some(Stone::White));
+        assert_eq!(board.get_at(7, 7), Some(Stone::White));
+        assert_eq!(board.get_at(7, 0), Some(Stone::White));
+        assert_eq!(board.get_at(0, 7), None);
+
+        let mut bits = crate::bitboard::BitBoard::new();
+        bits.init_with_handicap(3, Stone::White).unwrap();
+        assert_eq!(bits.board(), board.board());
+
+        assert_eq!(
+            board.init_with_handicap(5, Stone::White),
+            Err(ReversiError::IndexOutOfBound)
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn deserialize_checks_size() {
+        let mut board = ArrayBasedBoard::new();
+        board.init_four_central_squares();
+        let json = serde_json::to_string(&board).unwrap();
+        let read: ArrayBasedBoard = serde_json::from_str(&json).unwrap();
+        assert_eq!(read.board(), board.board());
+        assert_eq!(read.hash(), board.hash());
+
+        for json in [
+            r#"{"board":[[null]]}"#,
+            r#"{"board":[[null,null,null,null],[],[],[]]}"#,
+        ] {
+            assert!(serde_json::from_str::<ArrayBasedBoard>(json).is_err());
+        }
+    }
 }
@@ -0,0 +1,297 @@
+// Opening books, keyed by the first of the 8 symmetric position strings. Book
+// files have a position string, a move and a weight per line; lines starting
+// with '#' are ignored.
+
+use std::{
+    cmp::Reverse,
+    collections::HashMap,
+    io::{BufRead, BufReader, Read, Write},
+    sync::Arc,
+    time::Duration,
+};
+
+use crate::{
+    background::{CancelToken, Progress},
+    board::ReversiBoard,
+    computer::Computer,
+    error::BookError,
+    game::{Move, SimpleReversiGame},
+    point::Point,
+    position::Position,
+    stone::Stone,
+    symmetry,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BookMove {
+    pub point: Point,
+    /// How good the move is, e.g. how often it was played. Higher is better.
+    pub weight: u32,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OpeningBook {
+    entries: HashMap<String, Vec<BookMove>>,
+}
+
+impl OpeningBook {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builds a book from the first `plies` moves of every transcript.
+    pub fn from_transcripts<'a, I>(transcripts: I, plies: usize) -> Result<Self, BookError>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        let mut book = Self::new();
+        for transcript in transcripts {
+            book.add_transcript(transcript, plies)?;
+        }
+
+        Ok(book)
+    }
+
+    /// Adds the first `plies` moves of `transcript`, each with a weight of 1.
+    pub fn add_transcript(&mut self, transcript: &str, plies: usize) -> Result<(), BookError> {
+        let played = SimpleReversiGame::from_transcript(transcript)?;
+        let mut game = SimpleReversiGame::new();
+
+        for m in played.history().iter().take(plies) {
+            // Passes are made by `put_stone` on its own.
+            if let Move::Put { player, point, .. } = m {
+                self.add(game.board(), *player, *point, 1);
+                game.put_stone(point.x, point.y).unwrap();
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Adds `weight` to the weight of `point` in the position.
+    pub fn add(&mut self, board: &dyn ReversiBoard, turn: Stone, point: Point, weight: u32) {
+        let position = Position::from_board(board, turn);
+        let (canonical, symmetry) = position.canonical();
+        let key = canonical.to_string();
+
+        // Moves that are the same up to a symmetry of the position itself
+        // are stored as one.
+        let point = symmetry::invariants(position.cells())
+            .into_iter()
+            .map(|invariant| symmetry.apply(invariant.apply(point, board.size()), board.size()))
+            .min_by_key(|p| (p.y, p.x))
+            .unwrap();
+
+        let moves = self.entries.entry(key).or_default();
+        match moves.iter_mut().find(|m| m.point == point) {
+            Some(m) => m.weight += weight,
+            None => moves.push(BookMove { point, weight }),
+        }
+    }
+
+    /// The book moves in the position, heaviest first.
+    pub fn lookup(&self, board: &dyn ReversiBoard, turn: Stone) -> Vec<BookMove> {
+        let (canonical, symmetry) = Position::from_board(board, turn).canonical();
+        let Some(moves) = self.entries.get(&canonical.to_string()) else {
+            return Vec::new();
+        };
+
+        let mut moves: Vec<BookMove> = moves
+            .iter()
+            .map(|m| BookMove {
+                point: symmetry.inverse().apply(m.point, board.size()),
+                weight: m.weight,
+            })
+            .collect();
+        moves.sort_by_key(|m| Reverse(m.weight));
+
+        moves
+    }
+
+    /// Number of positions in the book.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Reads a book file, adding up the weights of repeated moves.
+    pub fn read<R: Read>(reader: R) -> Result<Self, BookError> {
+        let mut book = Self::new();
+
+        for (i, line) in BufReader::new(reader).lines().enumerate() {
+            let line = line?;
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let syntax = BookError::Syntax(i + 1);
+            let [squares, turn, point, weight] = line.split_whitespace().collect::<Vec<_>>()[..]
+            else {
+                return Err(syntax);
+            };
+
+            let position: Position = format!("{} {}", squares, turn)
+                .parse()
+                .map_err(|_| syntax)?;
+            let point = Point::parse_on(point, position.size()).map_err(|_| syntax)?;
+            let weight: u32 = weight.parse().map_err(|_| syntax)?;
+
+            book.add(position.to_board().as_ref(), position.turn(), point, weight);
+        }
+
+        Ok(book)
+    }
+
+    pub fn write<W: Write>(&self, writer: &mut W) -> Result<(), BookError> {
+        let mut keys: Vec<&String> = self.entries.keys().collect();
+        keys.sort();
+
+        for key in keys {
+            for m in &self.entries[key] {
+                writeln!(writer, "{} {} {}", key, m.point, m.weight)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Plays from `book` while the position is in it, and lets `computer`
+/// decide otherwise.
+pub struct BookComputer<C: Computer> {
+    color: Stone,
+    book: Arc<OpeningBook>,
+    computer: C,
+}
+
+impl<C: Computer> BookComputer<C> {
+    pub fn new(color: Stone, book: Arc<OpeningBook>, computer: C) -> Self {
+        Self {
+            color,
+            book,
+            computer,
+        }
+    }
+
+    #[inline]
+    pub fn book(&self) -> &Arc<OpeningBook> {
+        &self.book
+    }
+
+    fn book_move(&self, board: &dyn ReversiBoard) -> Option<Point> {
+        self.book
+            .lookup(board, self.color)
+            .into_iter()
+            .map(|m| m.point)
+            .find(|p| board.check_can_put(p.x, p.y, self.color))
+    }
+}
+
+impl<C: Computer> Computer for BookComputer<C> {
+    fn decide(&self, board: &dyn ReversiBoard) -> Point {
+        self.book_move(board)
+            .unwrap_or_else(|| self.computer.decide(board))
+    }
+
+    fn decide_within(&self, board: &dyn ReversiBoard, limit: Duration) -> Point {
+        self.book_move(board)
+            .unwrap_or_else(|| self.computer.decide_within(board, limit))
+    }
+
+    fn decide_with(
+        &self,
+        board: &dyn ReversiBoard,
+        token: &CancelToken,
+        report: &mut dyn FnMut(Progress),
+    ) -> Point {
+        self.book_move(board)
+            .unwrap_or_else(|| self.computer.decide_with(board, token, report))
+    }
+
+    fn seed(&self) -> Option<u64> {
+        self.computer.seed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::computer::SimpleComputer;
+
+    use super::*;
+
+    fn book() -> OpeningBook {
+        // e6 f4 is f5 d6 reflected in the a1-h8 diagonal.
+        OpeningBook::from_transcripts(["f5d6c3", "e6f4", "f5f6"], 2).unwrap()
+    }
+
+    #[test]
+    fn symmetric_lines() {
+        let book = book();
+        let game = SimpleReversiGame::new();
+
+        let moves = book.lookup(game.board(), Stone::Black);
+        assert_eq!(moves.len(), 1);
+        assert_eq!(moves[0].weight, 3);
+
+        let game = SimpleReversiGame::from_transcript("f5").unwrap();
+        let moves = book.lookup(game.board(), Stone::White);
+        assert_eq!(
+            moves,
+            vec![
+                BookMove {
+                    point: "d6".parse().unwrap(),
+                    weight: 2
+                },
+                BookMove {
+                    point: "f6".parse().unwrap(),
+                    weight: 1
+                },
+            ]
+        );
+
+        let game = SimpleReversiGame::from_transcript("d3").unwrap();
+        let moves = book.lookup(game.board(), Stone::White);
+        assert_eq!(moves[0].point, "c5".parse().unwrap());
+
+        let game = SimpleReversiGame::from_transcript("f5d6").unwrap();
+        assert!(book.lookup(game.board(), Stone::Black).is_empty());
+    }
+
+    #[test]
+    fn read_write() {
+        let book = book();
+
+        let mut bytes = Vec::new();
+        book.write(&mut bytes).unwrap();
+        assert_eq!(OpeningBook::read(bytes.as_slice()), Ok(book));
+
+        let text = "# start\n\n---------------------------OX------XO--------------------------- X f5 1\n";
+        let book = OpeningBook::read(text.as_bytes()).unwrap();
+        assert_eq!(book.len(), 1);
+
+        let text = "---------------------------OX------XO--------------------------- X f5\n";
+        assert_eq!(OpeningBook::read(text.as_bytes()), Err(BookError::Syntax(1)));
+    }
+
+    #[test]
+    fn book_computer() {
+        let computer = BookComputer::new(
+            Stone::White,
+            Arc::new(book()),
+            SimpleComputer::new(Stone::White),
+        );
+
+        let game = SimpleReversiGame::from_transcript("e6").unwrap();
+        assert_eq!(computer.decide(game.board()), "f4".parse().unwrap());
+
+        let game = SimpleReversiGame::from_transcript("f5d6c3").unwrap();
+        let decided = computer.decide(game.board());
+        assert!(game.board().check_can_put(decided.x, decided.y, Stone::White));
+    }
+}
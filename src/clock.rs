@@ -0,0 +1,102 @@
+// Time controls for timed games, and the clock a computer keeps for itself.
+
+use std::time::Duration;
+
+// Moves kept in reserve when sharing out the remaining time, so the clock
+// never runs out if the game goes on longer than expected.
+const RESERVE_MOVES: u32 = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeControl {
+    /// The same time for every move.
+    PerMove(Duration),
+    /// One budget for all the moves of the game.
+    SuddenDeath(Duration),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Clock {
+    control: TimeControl,
+    remaining: Duration,
+}
+
+impl Clock {
+    pub fn new(control: TimeControl) -> Self {
+        let remaining = match control {
+            TimeControl::PerMove(time) | TimeControl::SuddenDeath(time) => time,
+        };
+
+        Self { control, remaining }
+    }
+
+    #[inline]
+    pub fn control(&self) -> TimeControl {
+        self.control
+    }
+
+    /// Time left for the rest of the game, or for the current move with `PerMove`.
+    #[inline]
+    pub fn remaining(&self) -> Duration {
+        self.remaining
+    }
+
+    #[inline]
+    pub fn is_flagged(&self) -> bool {
+        self.remaining.is_zero()
+    }
+
+    /// Time to spend on the next move, with `empties` empty squares on the board.
+    pub fn allot(&self, empties: usize) -> Duration {
+        match self.control {
+            TimeControl::PerMove(time) => time,
+            TimeControl::SuddenDeath(_) => {
+                // Each player makes about half of the remaining moves.
+                let moves = (empties as u32).div_ceil(2) + RESERVE_MOVES;
+                self.remaining / moves
+            }
+        }
+    }
+
+    /// Records `elapsed` as spent on a move.
+    pub fn consume(&mut self, elapsed: Duration) {
+        if let TimeControl::SuddenDeath(_) = self.control {
+            self.remaining = self.remaining.saturating_sub(elapsed);
+        }
+    }
+
+    /// Winds the clock back to the start of a game.
+    pub fn reset(&mut self) {
+        *self = Self::new(self.control);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sudden_death() {
+        let mut clock = Clock::new(TimeControl::SuddenDeath(Duration::from_secs(300)));
+        assert_eq!(clock.allot(60), Duration::from_secs(300) / 34);
+
+        clock.consume(Duration::from_secs(290));
+        assert_eq!(clock.remaining(), Duration::from_secs(10));
+        assert_eq!(clock.allot(11), Duration::from_secs(1));
+
+        clock.consume(Duration::from_secs(11));
+        assert!(clock.is_flagged());
+        assert_eq!(clock.allot(10), Duration::ZERO);
+
+        clock.reset();
+        assert_eq!(clock.remaining(), Duration::from_secs(300));
+    }
+
+    #[test]
+    fn per_move() {
+        let mut clock = Clock::new(TimeControl::PerMove(Duration::from_millis(500)));
+        clock.consume(Duration::from_secs(1));
+
+        assert!(!clock.is_flagged());
+        assert_eq!(clock.allot(60), Duration::from_millis(500));
+    }
+}
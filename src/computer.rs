@@ -1,4 +1,28 @@
-use crate::{board::ReversiBoard, point::Point, stone::Stone};
+use std::{
+    cell::Cell,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use rand::{seq::SliceRandom, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::{
+    background::{CancelToken, Progress},
+    board::ReversiBoard,
+    clock::{Clock, TimeControl},
+    endgame,
+    evaluation::{Evaluator, Positional},
+    point::Point,
+    position::Position,
+    stone::Stone,
+    transposition::{Bound, Entry, TranspositionTable},
+    zobrist,
+};
 
 pub enum PlayerType {
     Human,
@@ -7,23 +31,64 @@ pub enum PlayerType {
 
 pub trait Computer {
     fn decide(&self, board: &dyn ReversiBoard) -> Point;
+
+    /// Decides within about `limit`. Computers that can't bound their
+    /// thinking time just decide.
+    fn decide_within(&self, board: &dyn ReversiBoard, limit: Duration) -> Point {
+        let _ = limit;
+        self.decide(board)
+    }
+
+    /// Decides like `decide`, but stops early when `token` is cancelled and
+    /// returns the best move found so far. Searching computers call `report`
+    /// as the search goes on.
+    fn decide_with(
+        &self,
+        board: &dyn ReversiBoard,
+        token: &CancelToken,
+        report: &mut dyn FnMut(Progress),
+    ) -> Point {
+        let _ = (token, report);
+        self.decide(board)
+    }
+
+    /// The seed of the computer's random choices, if it makes any. A computer
+    /// created with the same seed makes the same decisions in the same games,
+    /// and `new` constructors pick a random seed. Seeds drive `ChaCha8Rng`,
+    /// whose output doesn't change between versions or platforms.
+    fn seed(&self) -> Option<u64> {
+        None
+    }
 }
 
 pub struct RandomComputer {
     color: Stone,
+    seed: u64,
+    rng: Mutex<ChaCha8Rng>,
 }
 
 impl RandomComputer {
     pub fn new(color: Stone) -> Self {
-        Self { color }
+        Self::with_seed(color, rand::random())
+    }
+
+    pub fn with_seed(color: Stone, seed: u64) -> Self {
+        Self {
+            color,
+            seed,
+            rng: Mutex::new(ChaCha8Rng::seed_from_u64(seed)),
+        }
     }
 }
 
 impl Computer for RandomComputer {
     fn decide(&self, board: &dyn ReversiBoard) -> Point {
         let can_put_stones = board.get_can_put_stones(self.color);
-        let index = rand::random::<usize>() % can_put_stones.len();
-        can_put_stones[index]
+        *can_put_stones.choose(&mut *self.rng.lock().unwrap()).unwrap()
+    }
+
+    fn seed(&self) -> Option<u64> {
+        Some(self.seed)
     }
 }
 
@@ -58,65 +123,653 @@ impl Computer for SimpleComputer {
 
 pub struct WeightedComputer {
     color: Stone,
-    weights: [[i32; 8]; 8],
+    positional: Positional,
 }
 
 impl WeightedComputer {
     pub fn new(color: Stone) -> Self {
-        Self { color, weights: WeightedComputer::DEFAULT_WEIGHTS }
+        Self { color, positional: Positional::new() }
     }
 
     pub fn with_weights(color: Stone, weights: [[i32; 8]; 8]) -> Self {
         Self {
             color,
-            weights
+            positional: Positional::with_weights(weights)
         }
     }
+}
+
+impl WeightedComputer {
+    pub(crate) fn best_move(&self, board: &dyn ReversiBoard, player: Stone) -> Point {
+        let can_put_stones = board.get_can_put_stones(player);
+
+        let mut max_count: i32 = i32::MIN;
+        let mut max_index: usize = 0;
+
+        for (i, p) in can_put_stones.iter().enumerate() {
+            let mut cloned_board = dyn_clone::clone_box(board);
+            let _ = cloned_board.put_stone(p.x, p.y, player);
+
+            let diff = self.positional.evaluate(cloned_board.as_ref(), player);
+            if diff > max_count {
+                max_count = diff;
+                max_index = i;
+            }
+        }
 
-    const DEFAULT_WEIGHTS: [[i32; 8]; 8] = [
-        [150, -50, 20, 10, 10, 20, -50, 150],
-        [-50, -70, -3, -3, -3, -3, -70, -50],
-        [20, -3, 7, 3, 3, 7, -3, 20],
-        [10, -3, 3, 1, 1, 3, -3, 10],
-        [10, -3, 3, 1, 1, 3, -3, 10],
-        [20, -3, 7, 3, 3, 7, -3, 20],
-        [-50, -70, -3, -3, -3, -3, -70, -50],
-        [150, -50, 20, 10, 10, 20, -50, 150],
-    ];
+        can_put_stones[max_index]
+    }
 }
 
 impl Computer for WeightedComputer {
     fn decide(&self, board: &dyn ReversiBoard) -> Point {
-        let can_put_stones = board.get_can_put_stones(self.color);
+        self.best_move(board, self.color)
+    }
+}
 
-        let mut max_count: i32 = i32::MIN;
+const WIN_SCORE: i32 = 1_000_000;
+
+pub struct AlphaBetaComputer {
+    color: Stone,
+    depth: usize,
+    evaluator: Box<dyn Evaluator>,
+    table: Option<Arc<TranspositionTable>>,
+    clock: Option<Mutex<Clock>>,
+    threads: usize,
+}
+
+// Checking the time on every node would be slower than the search itself.
+// Must be a power of two.
+const NODES_PER_TIME_CHECK: u64 = 1024;
+
+// The state of one search, which stops at `deadline` if there is one, or
+// when `token` is cancelled.
+struct Search {
+    deadline: Option<Instant>,
+    token: Option<CancelToken>,
+    nodes: Cell<u64>,
+    aborted: Cell<bool>,
+}
+
+impl Search {
+    fn new(deadline: Option<Instant>, token: Option<CancelToken>) -> Self {
+        Self {
+            deadline,
+            token,
+            nodes: Cell::new(0),
+            aborted: Cell::new(false),
+        }
+    }
+
+    fn should_stop(&self) -> bool {
+        if self.aborted.get() {
+            return true;
+        }
+
+        let nodes = self.nodes.get() + 1;
+        self.nodes.set(nodes);
+
+        if nodes & (NODES_PER_TIME_CHECK - 1) == 0 {
+            let timeout = self.deadline.is_some_and(|deadline| Instant::now() >= deadline);
+            let cancelled = self.token.as_ref().is_some_and(|token| token.is_cancelled());
+            self.aborted.set(timeout || cancelled);
+        }
+
+        self.aborted.get()
+    }
+}
+
+impl AlphaBetaComputer {
+    pub fn new(color: Stone, depth: usize) -> Self {
+        Self::with_evaluator(color, depth, Positional::new())
+    }
+
+    /// Scores the positions at the end of the search with `evaluator`, which
+    /// can also be a closure.
+    pub fn with_evaluator<E: Evaluator + 'static>(color: Stone, depth: usize, evaluator: E) -> Self {
+        Self {
+            color,
+            depth: depth.max(1),
+            evaluator: Box::new(evaluator),
+            table: None,
+            clock: None,
+            threads: 1,
+        }
+    }
+
+    #[inline]
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    pub fn set_depth(&mut self, depth: usize) {
+        self.depth = depth.max(1);
+    }
+
+    pub fn set_evaluator<E: Evaluator + 'static>(&mut self, evaluator: E) {
+        self.evaluator = Box::new(evaluator);
+    }
+
+    #[inline]
+    pub fn threads(&self) -> usize {
+        self.threads
+    }
+
+    /// Splits the moves at the root among `threads` threads. The threads
+    /// share the transposition table, so set one as well.
+    pub fn set_threads(&mut self, threads: usize) {
+        self.threads = threads.max(1);
+    }
+
+    /// Shares `table` with this computer. The same table can be given to
+    /// several computers, including ones playing the other color.
+    pub fn set_transposition_table(&mut self, table: Arc<TranspositionTable>) {
+        self.table = Some(table);
+    }
+
+    #[inline]
+    pub fn transposition_table(&self) -> Option<&Arc<TranspositionTable>> {
+        self.table.as_ref()
+    }
+
+    /// Plays under `control` from now on. Timed moves search deeper and
+    /// deeper until their share of the time is used up, ignoring `depth`.
+    pub fn set_time_control(&mut self, control: TimeControl) {
+        self.clock = Some(Mutex::new(Clock::new(control)));
+    }
+
+    /// A copy of the clock, if this computer plays under a time control.
+    pub fn clock(&self) -> Option<Clock> {
+        self.clock
+            .as_ref()
+            .map(|clock| clock.lock().unwrap().clone())
+    }
+
+    /// Winds the clock back for a new game.
+    pub fn reset_clock(&self) {
+        if let Some(clock) = &self.clock {
+            clock.lock().unwrap().reset();
+        }
+    }
+
+    // Searches one ply deeper on every iteration, up to `max_depth`, starting
+    // with the best move of the last one, and returns the best move of the
+    // last iteration that finished.
+    fn iterative_deepening(
+        &self,
+        board: &dyn ReversiBoard,
+        search: &Search,
+        max_depth: usize,
+        report: &mut dyn FnMut(Progress),
+    ) -> Point {
+        let mut best = board.get_can_put_stones(self.color)[0];
+
+        for depth in 1..=max_depth {
+            match self.search_root(board, depth, Some(best), search) {
+                Some(p) => best = p,
+                None => break,
+            }
+
+            report(Progress {
+                depth,
+                best_move: best,
+                nodes: search.nodes.get(),
+            });
+
+            if search.deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                break;
+            }
+        }
+
+        best
+    }
+
+    // None if the search was stopped before it finished.
+    fn search_root(
+        &self,
+        board: &dyn ReversiBoard,
+        depth: usize,
+        first: Option<Point>,
+        search: &Search,
+    ) -> Option<Point> {
+        let mut can_put_stones = board.get_can_put_stones(self.color);
+
+        if let Some(table) = &self.table {
+            let key = zobrist::position_key(board, self.color);
+            order_first(
+                &mut can_put_stones,
+                table.probe(key).and_then(|e| e.best_move),
+            );
+        }
+        order_first(&mut can_put_stones, first);
+
+        if self.threads > 1 && can_put_stones.len() > 1 {
+            return self.search_root_parallel(board, depth, &can_put_stones, search);
+        }
+
+        let mut alpha = -WIN_SCORE * 2;
+        let beta = WIN_SCORE * 2;
         let mut max_index: usize = 0;
 
         for (i, p) in can_put_stones.iter().enumerate() {
             let mut cloned_board = dyn_clone::clone_box(board);
             let _ = cloned_board.put_stone(p.x, p.y, self.color);
 
-            let (mut me, mut enemy): (i32, i32) = (0, 0);
+            let score = -self.negamax(
+                search,
+                cloned_board.as_ref(),
+                self.color.opposite(),
+                depth - 1,
+                -beta,
+                -alpha,
+            );
+
+            if search.aborted.get() {
+                return None;
+            }
+
+            if score > alpha {
+                alpha = score;
+                max_index = i;
+            }
+        }
 
-            for (y, row) in cloned_board.board().iter().enumerate() {
-                for (x, &stone) in row.iter().enumerate() {
-                    if let Some(s) = stone {
-                        if s == self.color {
-                            me += Self::DEFAULT_WEIGHTS[y][x];
-                        } else if s == self.color.opposite() {
-                            enemy += self.weights[y][x];
+        Some(can_put_stones[max_index])
+    }
+
+    // Each thread takes the next move not searched yet, with the best score so
+    // far as alpha. The first moves are searched with a full window, so the
+    // best move is the same as in `search_root` up to ties.
+    fn search_root_parallel(
+        &self,
+        board: &dyn ReversiBoard,
+        depth: usize,
+        moves: &[Point],
+        search: &Search,
+    ) -> Option<Point> {
+        // `dyn ReversiBoard` isn't `Sync`, so each thread builds its own board
+        // from a position.
+        let position = Position::from_board(board, self.color);
+        let (deadline, token) = (search.deadline, search.token.clone());
+        let next = AtomicUsize::new(0);
+        let best = Mutex::new((-WIN_SCORE * 2, 0));
+        let beta = WIN_SCORE * 2;
+
+        let workers: Vec<(u64, bool)> = thread::scope(|scope| {
+            let handles: Vec<_> = (0..self.threads.min(moves.len()))
+                .map(|_| {
+                    scope.spawn(|| {
+                        let worker = Search::new(deadline, token.clone());
+                        let board = position.to_fast_board();
+
+                        loop {
+                            let i = next.fetch_add(1, Ordering::Relaxed);
+                            let Some(p) = moves.get(i) else {
+                                break;
+                            };
+
+                            let mut cloned_board = dyn_clone::clone_box(board.as_ref());
+                            let _ = cloned_board.put_stone(p.x, p.y, self.color);
+
+                            let alpha = best.lock().unwrap().0;
+                            let score = -self.negamax(
+                                &worker,
+                                cloned_board.as_ref(),
+                                self.color.opposite(),
+                                depth - 1,
+                                -beta,
+                                -alpha,
+                            );
+
+                            if worker.aborted.get() {
+                                break;
+                            }
+
+                            let mut best = best.lock().unwrap();
+                            if score > best.0 {
+                                *best = (score, i);
+                            }
                         }
-                    }
+
+                        (worker.nodes.get(), worker.aborted.get())
+                    })
+                })
+                .collect();
+
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+
+        for (nodes, aborted) in workers {
+            search.nodes.set(search.nodes.get() + nodes);
+            if aborted {
+                search.aborted.set(true);
+            }
+        }
+
+        if search.aborted.get() {
+            return None;
+        }
+
+        Some(moves[best.into_inner().unwrap().1])
+    }
+
+    fn negamax(
+        &self,
+        search: &Search,
+        board: &dyn ReversiBoard,
+        player: Stone,
+        depth: usize,
+        mut alpha: i32,
+        mut beta: i32,
+    ) -> i32 {
+        if search.should_stop() {
+            return 0;
+        }
+
+        let mut can_put_stones = board.get_can_put_stones(player);
+
+        if can_put_stones.is_empty() {
+            if board.get_can_put_stones(player.opposite()).is_empty() {
+                return final_score(board, player);
+            }
+
+            // Pass: the opponent moves on the same board
+            return -self.negamax(search, board, player.opposite(), depth, -beta, -alpha);
+        }
+
+        if depth == 0 {
+            return self.evaluator.evaluate(board, player);
+        }
+
+        let key = zobrist::position_key(board, player);
+        if let Some(entry) = self.table.as_ref().and_then(|table| table.probe(key)) {
+            if entry.depth as usize >= depth {
+                match entry.bound {
+                    Bound::Exact => return entry.score,
+                    Bound::Lower => alpha = alpha.max(entry.score),
+                    Bound::Upper => beta = beta.min(entry.score),
+                }
+                if alpha >= beta {
+                    return entry.score;
                 }
             }
 
-            let diff = me - enemy;
-            if diff > max_count {
-                max_count = diff;
-                max_index = i;
+            order_first(&mut can_put_stones, entry.best_move);
+        }
+
+        let original_alpha = alpha;
+        let mut best = -WIN_SCORE * 2;
+        let mut best_move = can_put_stones[0];
+        for p in can_put_stones {
+            let mut cloned_board = dyn_clone::clone_box(board);
+            let _ = cloned_board.put_stone(p.x, p.y, player);
+
+            let score = -self.negamax(
+                search,
+                cloned_board.as_ref(),
+                player.opposite(),
+                depth - 1,
+                -beta,
+                -alpha,
+            );
+
+            if search.aborted.get() {
+                return 0;
+            }
+
+            if score > best {
+                best = score;
+                best_move = p;
+            }
+            if best > alpha {
+                alpha = best;
+            }
+            if alpha >= beta {
+                break;
             }
         }
 
-        can_put_stones[max_index]
+        if let Some(table) = &self.table {
+            let bound = if best <= original_alpha {
+                Bound::Upper
+            } else if best >= beta {
+                Bound::Lower
+            } else {
+                Bound::Exact
+            };
+
+            table.store(
+                key,
+                Entry {
+                    depth: depth.min(u8::MAX as usize) as u8,
+                    score: best,
+                    bound,
+                    best_move: Some(best_move),
+                },
+            );
+        }
+
+        best
+    }
+}
+
+fn order_first(moves: &mut [Point], first: Option<Point>) {
+    if let Some(index) = first.and_then(|first| moves.iter().position(|&p| p == first)) {
+        moves[..=index].rotate_right(1);
+    }
+}
+
+fn final_score(board: &dyn ReversiBoard, player: Stone) -> i32 {
+    let diff = board.count(player) as i32 - board.count(player.opposite()) as i32;
+
+    match diff {
+        d if d > 0 => WIN_SCORE + d,
+        d if d < 0 => -WIN_SCORE + d,
+        _ => 0,
+    }
+}
+
+impl Computer for AlphaBetaComputer {
+    fn decide(&self, board: &dyn ReversiBoard) -> Point {
+        self.decide_with(board, &CancelToken::new(), &mut |_| {})
+    }
+
+    fn decide_within(&self, board: &dyn ReversiBoard, limit: Duration) -> Point {
+        let search = Search::new(Some(Instant::now() + limit), None);
+        self.iterative_deepening(board, &search, endgame::empties(board), &mut |_| {})
+    }
+
+    fn decide_with(
+        &self,
+        board: &dyn ReversiBoard,
+        token: &CancelToken,
+        report: &mut dyn FnMut(Progress),
+    ) -> Point {
+        let Some(clock) = &self.clock else {
+            let search = Search::new(None, Some(token.clone()));
+            return self.iterative_deepening(board, &search, self.depth, report);
+        };
+
+        let start = Instant::now();
+        let limit = clock.lock().unwrap().allot(endgame::empties(board));
+        let search = Search::new(Some(start + limit), Some(token.clone()));
+        let decided = self.iterative_deepening(board, &search, endgame::empties(board), report);
+        clock.lock().unwrap().consume(start.elapsed());
+
+        decided
+    }
+}
+
+/// The score of a search to the computer's depth, in the units of its
+/// evaluator, or beyond `WIN_SCORE` when the game ends within the search.
+impl Evaluator for AlphaBetaComputer {
+    fn evaluate(&self, board: &dyn ReversiBoard, player: Stone) -> i32 {
+        let search = Search::new(None, None);
+        self.negamax(&search, board, player, self.depth, -WIN_SCORE * 2, WIN_SCORE * 2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{board::ArrayBasedBoard, game::SimpleReversiGame, position::PASS_POSITION};
+
+    use super::*;
+
+    fn disc_difference(board: &dyn ReversiBoard, player: Stone) -> i32 {
+        board.count(player) as i32 - board.count(player.opposite()) as i32
+    }
+
+    #[test]
+    fn random_computer_seed() {
+        let game = SimpleReversiGame::from_transcript("f5d6c3d3c4").unwrap();
+        let decide = |computer: &RandomComputer| -> Vec<Point> {
+            (0..20).map(|_| computer.decide(game.board())).collect()
+        };
+
+        let computer = RandomComputer::with_seed(Stone::White, 24);
+        assert_eq!(computer.seed(), Some(24));
+        assert_eq!(decide(&computer), decide(&RandomComputer::with_seed(Stone::White, 24)));
+        assert_ne!(decide(&computer), decide(&RandomComputer::with_seed(Stone::White, 25)));
+    }
+
+    #[test]
+    fn alpha_beta_depth_one_is_greedy() {
+        let mut board = ArrayBasedBoard::new();
+        board.init_four_central_squares();
+        board.put_stone(3, 2, Stone::Black).unwrap();
+
+        let greedy = SimpleComputer::new(Stone::White).decide(&board);
+        let alpha_beta =
+            AlphaBetaComputer::with_evaluator(Stone::White, 1, disc_difference).decide(&board);
+
+        assert_eq!(
+            board.count_flippable(greedy.x, greedy.y, Stone::White),
+            board.count_flippable(alpha_beta.x, alpha_beta.y, Stone::White)
+        );
+    }
+
+    #[test]
+    fn alpha_beta_with_transposition_table() {
+        let game = SimpleReversiGame::from_transcript("f5d6c3d3c4f4").unwrap();
+        let table = Arc::new(TranspositionTable::new(1 << 16));
+
+        let plain = AlphaBetaComputer::new(Stone::White, 4);
+        let mut cached = AlphaBetaComputer::new(Stone::White, 4);
+        cached.set_transposition_table(table.clone());
+
+        let score = |computer: &AlphaBetaComputer| {
+            computer.negamax(
+                &Search::new(None, None),
+                game.board(),
+                Stone::White,
+                4,
+                -WIN_SCORE * 2,
+                WIN_SCORE * 2,
+            )
+        };
+        assert_eq!(score(&cached), score(&plain));
+        assert_eq!(score(&cached), score(&plain));
+        assert!(table.probe(zobrist::position_key(game.board(), Stone::White)).is_some());
+
+        let decided = cached.decide(game.board());
+        assert!(game.board().check_can_put(decided.x, decided.y, Stone::White));
+    }
+
+    #[test]
+    fn alpha_beta_finds_win_through_pass() {
+        // Black takes the last white stone after White passes.
+        let board = PASS_POSITION.parse::<Position>().unwrap().to_board();
+
+        let computer = AlphaBetaComputer::with_evaluator(Stone::Black, 4, disc_difference);
+        let decided = computer.decide(board.as_ref());
+
+        assert!(board.check_can_put(decided.x, decided.y, Stone::Black));
+        assert_eq!(
+            computer.negamax(
+                &Search::new(None, None),
+                board.as_ref(),
+                Stone::Black,
+                4,
+                -WIN_SCORE * 2,
+                WIN_SCORE * 2
+            ),
+            WIN_SCORE + 6
+        );
+    }
+
+    #[test]
+    fn iterative_deepening_stops_in_time() {
+        let game = SimpleReversiGame::from_transcript("f5d6c3d3c4f4").unwrap();
+        let mut computer = AlphaBetaComputer::new(Stone::White, 1);
+
+        let start = Instant::now();
+        let decided = computer.decide_within(game.board(), Duration::from_millis(50));
+        assert!(start.elapsed() < Duration::from_secs(1));
+        assert!(game.board().check_can_put(decided.x, decided.y, Stone::White));
+
+        computer.set_time_control(TimeControl::SuddenDeath(Duration::from_secs(2)));
+        let decided = computer.decide(game.board());
+        assert!(game.board().check_can_put(decided.x, decided.y, Stone::White));
+
+        let clock = computer.clock().unwrap();
+        assert!(clock.remaining() < Duration::from_secs(2));
+        assert!(!clock.is_flagged());
+
+        computer.reset_clock();
+        assert_eq!(
+            computer.clock().unwrap().remaining(),
+            Duration::from_secs(2)
+        );
+    }
+
+    #[test]
+    fn iterative_deepening_finds_win_through_pass() {
+        let mut board = PASS_POSITION.parse::<Position>().unwrap().to_board();
+
+        let computer = AlphaBetaComputer::with_evaluator(Stone::Black, 1, disc_difference);
+        let decided = computer.decide_within(board.as_ref(), Duration::from_millis(200));
+
+        board.put_stone(decided.x, decided.y, Stone::Black).unwrap();
+        assert!(board.get_can_put_stones(Stone::White).is_empty());
+    }
+
+    #[test]
+    fn parallel_search_finds_equal_move() {
+        let game = SimpleReversiGame::from_transcript("f5d6c3d3c4f4f6f3e6e7").unwrap();
+        let table = Arc::new(TranspositionTable::new(1 << 16));
+
+        let single = AlphaBetaComputer::new(Stone::Black, 5);
+        let mut parallel = AlphaBetaComputer::new(Stone::Black, 5);
+        parallel.set_threads(4);
+        parallel.set_transposition_table(table);
+        assert_eq!(parallel.threads(), 4);
+
+        let score = |p: Point| {
+            let mut board = dyn_clone::clone_box(game.board());
+            board.put_stone(p.x, p.y, Stone::Black).unwrap();
+            -single.negamax(
+                &Search::new(None, None),
+                board.as_ref(),
+                Stone::White,
+                4,
+                -WIN_SCORE * 2,
+                WIN_SCORE * 2,
+            )
+        };
+
+        assert_eq!(
+            score(parallel.decide(game.board())),
+            score(single.decide(game.board()))
+        );
+    }
+
+    #[test]
+    fn weighted_computer_uses_its_weights() {
+        let game = SimpleReversiGame::new();
+        let mut weights = [[0; 8]; 8];
+        weights[5][4] = 100;
+
+        let computer = WeightedComputer::with_weights(Stone::Black, weights);
+        assert_eq!(computer.decide(game.board()), Point::new(4, 5));
     }
 }
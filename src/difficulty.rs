@@ -0,0 +1,201 @@
+// Difficulty levels for casual play.
+
+use std::sync::{Arc, Mutex};
+
+use rand::{seq::SliceRandom, Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::{
+    board::ReversiBoard,
+    book::{BookComputer, OpeningBook},
+    computer::{AlphaBetaComputer, Computer},
+    evaluation::{Evaluator, Positional},
+    point::Point,
+    stone::Stone,
+    tournament::DEFAULT_OPENINGS,
+};
+
+pub const MIN_LEVEL: u8 = 1;
+pub const MAX_LEVEL: u8 = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Difficulty {
+    pub depth: usize,
+    /// The largest noise added to an evaluation, in the units of `Positional`.
+    pub noise: i32,
+    /// The chance of playing a random move instead of searching.
+    pub blunder_chance: f64,
+    pub use_book: bool,
+}
+
+const LEVELS: [Difficulty; MAX_LEVEL as usize] = [
+    Difficulty::new(1, 80, 0.30, false),
+    Difficulty::new(1, 50, 0.20, false),
+    Difficulty::new(2, 40, 0.12, false),
+    Difficulty::new(2, 25, 0.08, false),
+    Difficulty::new(3, 20, 0.05, true),
+    Difficulty::new(3, 10, 0.03, true),
+    Difficulty::new(4, 5, 0.01, true),
+    Difficulty::new(5, 0, 0.0, true),
+    Difficulty::new(6, 0, 0.0, true),
+    Difficulty::new(8, 0, 0.0, true),
+];
+
+impl Difficulty {
+    pub const fn new(depth: usize, noise: i32, blunder_chance: f64, use_book: bool) -> Self {
+        Self {
+            depth,
+            noise,
+            blunder_chance,
+            use_book,
+        }
+    }
+
+    /// The configuration of `level`, clamped to `MIN_LEVEL..=MAX_LEVEL`.
+    pub fn level(level: u8) -> Self {
+        LEVELS[(level.clamp(MIN_LEVEL, MAX_LEVEL) - MIN_LEVEL) as usize]
+    }
+
+    /// `book` is only used if the difficulty says so, and defaults to a book
+    /// of `DEFAULT_OPENINGS`.
+    pub fn computer(&self, color: Stone, book: Option<Arc<OpeningBook>>) -> Box<dyn Computer> {
+        self.computer_with_seed(color, book, rand::random())
+    }
+
+    pub fn computer_with_seed(
+        &self,
+        color: Stone,
+        book: Option<Arc<OpeningBook>>,
+        seed: u64,
+    ) -> Box<dyn Computer> {
+        let computer = LevelComputer::with_seed(color, *self, seed);
+        if !self.use_book {
+            return Box::new(computer);
+        }
+
+        let book = book.unwrap_or_else(|| {
+            Arc::new(OpeningBook::from_transcripts(DEFAULT_OPENINGS, usize::MAX).unwrap())
+        });
+        Box::new(BookComputer::new(color, book, computer))
+    }
+}
+
+// `Positional` with uniform noise in `-noise..=noise` added.
+struct Noisy {
+    noise: i32,
+    rng: Mutex<ChaCha8Rng>,
+}
+
+impl Evaluator for Noisy {
+    fn evaluate(&self, board: &dyn ReversiBoard, player: Stone) -> i32 {
+        let noise = self.rng.lock().unwrap().gen_range(-self.noise..=self.noise);
+
+        Positional::new().evaluate(board, player) + noise
+    }
+}
+
+/// Plays at a `Difficulty`, except for the opening book.
+pub struct LevelComputer {
+    color: Stone,
+    blunder_chance: f64,
+    search: AlphaBetaComputer,
+    seed: u64,
+    rng: Mutex<ChaCha8Rng>,
+}
+
+impl LevelComputer {
+    pub fn new(color: Stone, difficulty: Difficulty) -> Self {
+        Self::with_seed(color, difficulty, rand::random())
+    }
+
+    pub fn with_seed(color: Stone, difficulty: Difficulty, seed: u64) -> Self {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let search = if difficulty.noise > 0 {
+            let noisy = Noisy {
+                noise: difficulty.noise,
+                rng: Mutex::new(ChaCha8Rng::seed_from_u64(rng.gen())),
+            };
+            AlphaBetaComputer::with_evaluator(color, difficulty.depth.max(1), noisy)
+        } else {
+            AlphaBetaComputer::new(color, difficulty.depth.max(1))
+        };
+
+        Self {
+            color,
+            blunder_chance: difficulty.blunder_chance,
+            search,
+            seed,
+            rng: Mutex::new(rng),
+        }
+    }
+}
+
+impl Computer for LevelComputer {
+    fn decide(&self, board: &dyn ReversiBoard) -> Point {
+        let mut rng = self.rng.lock().unwrap();
+
+        if rng.gen_bool(self.blunder_chance.clamp(0.0, 1.0)) {
+            return *board.get_can_put_stones(self.color).choose(&mut *rng).unwrap();
+        }
+
+        self.search.decide(board)
+    }
+
+    fn seed(&self) -> Option<u64> {
+        Some(self.seed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{computer::SimpleComputer, game::SimpleReversiGame, tournament};
+
+    use super::*;
+
+    #[test]
+    fn levels() {
+        assert_eq!(Difficulty::level(0), Difficulty::level(MIN_LEVEL));
+        assert_eq!(Difficulty::level(200), Difficulty::level(MAX_LEVEL));
+
+        for level in MIN_LEVEL..MAX_LEVEL {
+            let (easier, harder) = (Difficulty::level(level), Difficulty::level(level + 1));
+            assert!(easier.depth <= harder.depth);
+            assert!(easier.noise >= harder.noise);
+            assert!(easier.blunder_chance >= harder.blunder_chance);
+        }
+    }
+
+    #[test]
+    fn same_seed_same_game() {
+        let play = |seed| {
+            let difficulty = Difficulty::level(2);
+            let black = difficulty.computer_with_seed(Stone::Black, None, seed);
+            let white = SimpleComputer::new(Stone::White);
+            tournament::play_game("", black.as_ref(), &white)
+        };
+
+        let results: Vec<i32> = (0..6).map(play).collect();
+        assert_eq!(results, (0..6).map(play).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn book_and_handicap() {
+        let book = Arc::new(OpeningBook::from_transcripts(["f5f6"], 2).unwrap());
+
+        let computer = Difficulty::level(MAX_LEVEL).computer(Stone::White, Some(book.clone()));
+        let game = SimpleReversiGame::from_transcript("f5").unwrap();
+        assert_eq!(computer.decide(game.board()), "f6".parse().unwrap());
+
+        // Without a book, the default openings are played.
+        let computer = Difficulty::level(MAX_LEVEL).computer(Stone::Black, None);
+        let game = SimpleReversiGame::from_transcript("f5d6c5f4").unwrap();
+        assert_eq!(computer.decide(game.board()), "e3".parse().unwrap());
+
+        // A level without the book still plays legal moves from a handicap.
+        let computer = Difficulty::level(MIN_LEVEL).computer(Stone::White, Some(book));
+        let game = SimpleReversiGame::with_handicap(4, Stone::Black, Stone::White).unwrap();
+        assert_eq!(game.turn(), Stone::White);
+        let p = computer.decide(game.board());
+        assert!(game.check_can_put(p.x, p.y));
+    }
+}
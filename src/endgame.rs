@@ -0,0 +1,589 @@
+// Exact endgame search. Scores are the final disc differential from the
+// point of view of the player to move, counting stones only, as in `Score`.
+
+use crate::{
+    bitboard,
+    board::{ReversiBoard, DEFAULT_BOARD_SIZE},
+    computer::Computer,
+    point::Point,
+    position::Position,
+    stability,
+    stone::Stone,
+    transposition::{Bound, Entry, TranspositionTable},
+    zobrist,
+};
+
+// Below this many empties, sorting by mobility, probing the table and
+// counting stable discs cost more than they save.
+const FASTEST_FIRST_EMPTIES: usize = 7;
+const TABLE_CAPACITY: usize = 1 << 18;
+const SQUARES: i32 = (DEFAULT_BOARD_SIZE * DEFAULT_BOARD_SIZE) as i32;
+// There are never more legal moves than empty squares.
+const MAX_MOVES: usize = 64;
+// The squares of each quadrant of an 8x8 board, in the order of `region`.
+const QUADRANTS: [u64; 4] = [
+    0x0000_0000_0F0F_0F0F,
+    0x0000_0000_F0F0_F0F0,
+    0x0F0F_0F0F_0000_0000,
+    0xF0F0_F0F0_0000_0000,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Solution {
+    pub score: i32,
+    /// None if the player to move has to pass, or the game is over.
+    pub best_move: Option<Point>,
+    pub nodes: u64,
+}
+
+/// Solves `board` with `player` to move. The cost grows exponentially with
+/// the number of empty squares; around 20 is the practical limit on 8x8
+/// boards, which are searched on bit masks.
+pub fn solve(board: &dyn ReversiBoard, player: Stone) -> Solution {
+    let mut solver = Solver {
+        nodes: 0,
+        table: TranspositionTable::new(TABLE_CAPACITY),
+    };
+
+    let size = board.size();
+    let max = (size * size) as i32;
+    let (score, best_move) = if size == DEFAULT_BOARD_SIZE {
+        let mask = |stone| {
+            (0..SQUARES as usize)
+                .filter(|&i| board.get_at(i % size, i / size) == Some(stone))
+                .fold(0, |mask, i| mask | 1 << i)
+        };
+        let (own, opponent) = (mask(player), mask(player.opposite()));
+        solver.search_masks(own, opponent, -max - 1, max + 1, false)
+    } else {
+        let board = Position::from_board(board, player).to_board();
+        solver.search(board.as_ref(), player, -max - 1, max + 1, false)
+    };
+
+    Solution {
+        score,
+        best_move,
+        nodes: solver.nodes,
+    }
+}
+
+pub fn empties(board: &dyn ReversiBoard) -> usize {
+    board.size() * board.size() - board.count(Stone::Black) - board.count(Stone::White)
+}
+
+struct Solver {
+    nodes: u64,
+    table: TranspositionTable,
+}
+
+impl Solver {
+    fn search(
+        &mut self,
+        board: &dyn ReversiBoard,
+        player: Stone,
+        mut alpha: i32,
+        mut beta: i32,
+        passed: bool,
+    ) -> (i32, Option<Point>) {
+        self.nodes += 1;
+
+        let moves = board.get_can_put_stones(player);
+        if moves.is_empty() {
+            if passed {
+                let score = board.count(player) as i32 - board.count(player.opposite()) as i32;
+                return (score, None);
+            }
+
+            let (score, _) = self.search(board, player.opposite(), -beta, -alpha, true);
+            return (-score, None);
+        }
+
+        let empties = empties(board);
+
+        // The opponent's stable discs are never ours, which caps the final
+        // differential. Only worth computing when the cap may be below alpha.
+        let squares = (board.size() * board.size()) as i32;
+        let opponent = board.count(player.opposite()) as i32;
+        if empties > FASTEST_FIRST_EMPTIES && squares - 2 * opponent <= alpha {
+            let max = squares - 2 * stability::count_stable(board, player.opposite()) as i32;
+            if max <= alpha {
+                return (max, None);
+            }
+        }
+
+        let key = zobrist::position_key(board, player);
+        let mut first = None;
+        if empties > FASTEST_FIRST_EMPTIES {
+            if let Some(entry) = self.table.probe(key) {
+                match entry.bound {
+                    Bound::Exact => return (entry.score, entry.best_move),
+                    Bound::Lower => alpha = alpha.max(entry.score),
+                    Bound::Upper => beta = beta.min(entry.score),
+                }
+                if alpha >= beta {
+                    return (entry.score, entry.best_move);
+                }
+
+                first = entry.best_move;
+            }
+        }
+
+        let children = ordered_children(board, player, moves, first);
+
+        let original_alpha = alpha;
+        let mut best = i32::MIN;
+        let mut best_move = None;
+        for (i, (point, child)) in children.into_iter().enumerate() {
+            let child = child.as_ref();
+
+            // Proves the later moves worse with a null window, and searches
+            // again only when one is not.
+            let mut score = if i == 0 {
+                -self
+                    .search(child, player.opposite(), -beta, -alpha, false)
+                    .0
+            } else {
+                -self
+                    .search(child, player.opposite(), -alpha - 1, -alpha, false)
+                    .0
+            };
+            if i > 0 && alpha < score && score < beta {
+                score = -self
+                    .search(child, player.opposite(), -beta, -score, false)
+                    .0;
+            }
+
+            if score > best {
+                best = score;
+                best_move = Some(point);
+            }
+            if best > alpha {
+                alpha = best;
+            }
+            if alpha >= beta {
+                break;
+            }
+        }
+
+        if empties > FASTEST_FIRST_EMPTIES {
+            let bound = if best <= original_alpha {
+                Bound::Upper
+            } else if best >= beta {
+                Bound::Lower
+            } else {
+                Bound::Exact
+            };
+
+            self.table.store(
+                key,
+                Entry {
+                    depth: empties as u8,
+                    score: best,
+                    bound,
+                    best_move,
+                },
+            );
+        }
+
+        (best, best_move)
+    }
+
+    // `search` on an 8x8 board, as the masks of the player to move and of
+    // the opponent.
+    fn search_masks(
+        &mut self,
+        own: u64,
+        opponent: u64,
+        mut alpha: i32,
+        mut beta: i32,
+        passed: bool,
+    ) -> (i32, Option<Point>) {
+        self.nodes += 1;
+
+        let moves = bitboard::moves_of(own, opponent);
+        if moves == 0 {
+            if passed {
+                return (own.count_ones() as i32 - opponent.count_ones() as i32, None);
+            }
+
+            let (score, _) = self.search_masks(opponent, own, -beta, -alpha, true);
+            return (-score, None);
+        }
+
+        let empties = (!(own | opponent)).count_ones() as usize;
+
+        if empties > FASTEST_FIRST_EMPTIES && SQUARES - 2 * opponent.count_ones() as i32 <= alpha {
+            let stable = stability::stable_mask(opponent, own | opponent);
+            let max = SQUARES - 2 * stable.count_ones() as i32;
+            if max <= alpha {
+                return (max, None);
+            }
+        }
+
+        let key = zobrist::mask_key(own, opponent);
+        let mut first = None;
+        if empties > FASTEST_FIRST_EMPTIES {
+            if let Some(entry) = self.table.probe(key) {
+                match entry.bound {
+                    Bound::Exact => return (entry.score, entry.best_move),
+                    Bound::Lower => alpha = alpha.max(entry.score),
+                    Bound::Upper => beta = beta.min(entry.score),
+                }
+                if alpha >= beta {
+                    return (entry.score, entry.best_move);
+                }
+
+                first = entry.best_move;
+            }
+        }
+
+        let mut children = [Child::default(); MAX_MOVES];
+        let count = ordered_moves(own, opponent, moves, first, &mut children);
+
+        let original_alpha = alpha;
+        let mut best = i32::MIN;
+        let mut best_move = None;
+        for (i, child) in children[..count].iter().enumerate() {
+            let child_own = opponent & !child.flips;
+            let child_opponent = own | child.flips | child.square;
+
+            let mut score = if i == 0 {
+                -self.search_child(child_own, child_opponent, -beta, -alpha)
+            } else {
+                -self.search_child(child_own, child_opponent, -alpha - 1, -alpha)
+            };
+            if i > 0 && alpha < score && score < beta {
+                score = -self.search_child(child_own, child_opponent, -beta, -score);
+            }
+
+            if score > best {
+                best = score;
+                best_move = Some(point_of(child.square));
+            }
+            if best > alpha {
+                alpha = best;
+            }
+            if alpha >= beta {
+                break;
+            }
+        }
+
+        if empties > FASTEST_FIRST_EMPTIES {
+            let bound = if best <= original_alpha {
+                Bound::Upper
+            } else if best >= beta {
+                Bound::Lower
+            } else {
+                Bound::Exact
+            };
+
+            self.table.store(
+                key,
+                Entry {
+                    depth: empties as u8,
+                    score: best,
+                    bound,
+                    best_move,
+                },
+            );
+        }
+
+        (best, best_move)
+    }
+
+    fn search_child(&mut self, own: u64, opponent: u64, alpha: i32, beta: i32) -> i32 {
+        if (!(own | opponent)).count_ones() as usize > FASTEST_FIRST_EMPTIES {
+            self.search_masks(own, opponent, alpha, beta, false).0
+        } else {
+            self.search_shallow(own, opponent, alpha, beta, false)
+        }
+    }
+
+    // `search_masks` near the end of the game, where only parity is worth
+    // ordering the moves by, and a plain alpha-beta search is enough.
+    fn search_shallow(
+        &mut self,
+        own: u64,
+        opponent: u64,
+        mut alpha: i32,
+        beta: i32,
+        passed: bool,
+    ) -> i32 {
+        self.nodes += 1;
+
+        let moves = bitboard::moves_of(own, opponent);
+        if moves == 0 {
+            if passed {
+                return own.count_ones() as i32 - opponent.count_ones() as i32;
+            }
+
+            return -self.search_shallow(opponent, own, -beta, -alpha, true);
+        }
+
+        let empty = !(own | opponent);
+        let odd = QUADRANTS
+            .into_iter()
+            .filter(|&quadrant| (quadrant & empty).count_ones() & 1 == 1)
+            .fold(0, |odd, quadrant| odd | quadrant);
+
+        let mut best = i32::MIN;
+        for mut moves in [moves & odd, moves & !odd] {
+            while moves != 0 {
+                let square = moves & moves.wrapping_neg();
+                moves ^= square;
+
+                let flips = bitboard::flips_of(own, opponent, square);
+                let score = -self.search_shallow(
+                    opponent & !flips,
+                    own | flips | square,
+                    -beta,
+                    -alpha,
+                    false,
+                );
+
+                best = best.max(score);
+                alpha = alpha.max(best);
+                if alpha >= beta {
+                    return best;
+                }
+            }
+        }
+
+        best
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Child {
+    order: i32,
+    square: u64,
+    flips: u64,
+}
+
+#[inline]
+fn point_of(square: u64) -> Point {
+    let index = square.trailing_zeros() as usize;
+
+    Point::new(index % DEFAULT_BOARD_SIZE, index / DEFAULT_BOARD_SIZE)
+}
+
+// `ordered_children` on masks. Fills `children` and returns how many there are.
+fn ordered_moves(
+    own: u64,
+    opponent: u64,
+    mut moves: u64,
+    first: Option<Point>,
+    children: &mut [Child; MAX_MOVES],
+) -> usize {
+    let empty = !(own | opponent);
+    let fastest_first = empty.count_ones() as usize > FASTEST_FIRST_EMPTIES;
+    let first = first.map_or(0, |p| 1 << (p.y * DEFAULT_BOARD_SIZE + p.x));
+
+    let mut count = 0;
+    while moves != 0 {
+        let square = moves & moves.wrapping_neg();
+        moves ^= square;
+
+        let flips = bitboard::flips_of(own, opponent, square);
+        let odd = QUADRANTS
+            .iter()
+            .any(|&quadrant| quadrant & square != 0 && (quadrant & empty).count_ones() & 1 == 1);
+        let mut order = if odd { 0 } else { 1 };
+        if square == first {
+            order = i32::MIN;
+        } else if fastest_first {
+            let replies = bitboard::moves_of(opponent & !flips, own | flips | square);
+            order += 2 * replies.count_ones() as i32;
+        }
+
+        children[count] = Child {
+            order,
+            square,
+            flips,
+        };
+        count += 1;
+    }
+
+    children[..count].sort_unstable_by_key(|child| (child.order, child.square));
+
+    count
+}
+
+// Orders moves by parity (moves into regions with an odd number of empties
+// first), and with many empties left, fastest-first (fewest replies first).
+// `first`, the best move from the table, goes before all of them.
+fn ordered_children(
+    board: &dyn ReversiBoard,
+    player: Stone,
+    moves: Vec<Point>,
+    first: Option<Point>,
+) -> Vec<(Point, Box<dyn ReversiBoard + '_>)> {
+    let empties = empties(board);
+    let parity = region_parity(board);
+    let half = board.size() / 2;
+
+    let mut children: Vec<(i32, Point, Box<dyn ReversiBoard + '_>)> = moves
+        .into_iter()
+        .map(|p| {
+            let mut child = dyn_clone::clone_box(board);
+            let _ = child.put_stone(p.x, p.y, player);
+
+            let odd = parity[region(p, half)];
+            let mut key = if odd { 0 } else { 1 };
+            if empties > FASTEST_FIRST_EMPTIES {
+                key += 2 * child.get_can_put_stones(player.opposite()).len() as i32;
+            }
+
+            (key, p, child)
+        })
+        .collect();
+
+    children.sort_by_key(|(key, ..)| *key);
+
+    let mut children: Vec<_> = children
+        .into_iter()
+        .map(|(_, p, child)| (p, child))
+        .collect();
+    if let Some(index) = first.and_then(|first| children.iter().position(|(p, _)| *p == first)) {
+        children[..=index].rotate_right(1);
+    }
+
+    children
+}
+
+#[inline]
+fn region(p: Point, half: usize) -> usize {
+    (p.y / half) * 2 + p.x / half
+}
+
+// Whether each quadrant has an odd number of empty squares.
+fn region_parity(board: &dyn ReversiBoard) -> [bool; 4] {
+    let half = board.size() / 2;
+    let mut parity = [false; 4];
+
+    for y in 0..board.size() {
+        for x in 0..board.size() {
+            if board.get_at(x, y).is_none() {
+                parity[region(Point::new(x, y), half)] ^= true;
+            }
+        }
+    }
+
+    parity
+}
+
+/// A computer that plays perfectly by solving the position on every move.
+pub struct EndgameSolver {
+    color: Stone,
+}
+
+impl EndgameSolver {
+    pub fn new(color: Stone) -> Self {
+        Self { color }
+    }
+}
+
+impl Computer for EndgameSolver {
+    fn decide(&self, board: &dyn ReversiBoard) -> Point {
+        solve(board, self.color)
+            .best_move
+            .expect("no legal move to decide")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        computer::{PlayerType, WeightedComputer},
+        game::{PlayerManager, SimpleReversiGame},
+    };
+
+    use super::*;
+
+    fn minimax(board: &dyn ReversiBoard, player: Stone, passed: bool) -> i32 {
+        let moves = board.get_can_put_stones(player);
+        if moves.is_empty() {
+            if passed {
+                return board.count(player) as i32 - board.count(player.opposite()) as i32;
+            }
+            return -minimax(board, player.opposite(), true);
+        }
+
+        moves
+            .into_iter()
+            .map(|p| {
+                let mut child = dyn_clone::clone_box(board);
+                let _ = child.put_stone(p.x, p.y, player);
+                -minimax(child.as_ref(), player.opposite(), false)
+            })
+            .max()
+            .unwrap()
+    }
+
+    fn position_with_empties(n: usize) -> SimpleReversiGame {
+        let mut game = SimpleReversiGame::new();
+        let player_mgr = PlayerManager::new(
+            PlayerType::Computer(Box::new(WeightedComputer::new(Stone::Black))),
+            PlayerType::Computer(Box::new(WeightedComputer::new(Stone::White))),
+        );
+
+        while empties(game.board()) > n {
+            let p = player_mgr.decide(game.board(), game.turn()).unwrap();
+            game.put_stone(p.x, p.y).unwrap();
+        }
+
+        game
+    }
+
+    #[test]
+    fn matches_minimax() {
+        for n in [6, 9] {
+            let game = position_with_empties(n);
+            let solution = solve(game.board(), game.turn());
+
+            assert_eq!(solution.score, minimax(game.board(), game.turn(), false));
+
+            let best = solution.best_move.unwrap();
+            let mut child = dyn_clone::clone_box(game.board());
+            let _ = child.put_stone(best.x, best.y, game.turn());
+            assert_eq!(
+                solve(child.as_ref(), game.turn().opposite()).score,
+                -solution.score
+            );
+        }
+    }
+
+    #[test]
+    fn masks_match_boards() {
+        for n in [10, 12] {
+            let game = position_with_empties(n);
+            let solution = solve(game.board(), game.turn());
+
+            let mut solver = Solver {
+                nodes: 0,
+                table: TranspositionTable::new(TABLE_CAPACITY),
+            };
+            let board = Position::from_board(game.board(), game.turn()).to_board();
+            let (score, _) = solver.search(board.as_ref(), game.turn(), -65, 65, false);
+            assert_eq!(solution.score, score);
+        }
+    }
+
+    #[test]
+    fn game_over() {
+        let position: Position =
+            "XXXXXXXX XXXXXXXX XXXXXXXX XXXXXXXX XOOOOOOO OOOOOOOO OOOOOOOO OOOOOOOO O"
+                .parse()
+                .unwrap();
+        let board = position.to_board();
+
+        assert_eq!(
+            solve(board.as_ref(), Stone::White),
+            Solution {
+                score: -2,
+                best_move: None,
+                nodes: 2,
+            }
+        );
+    }
+}
@@ -1,13 +1,132 @@
 use crate::stone::Stone;
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ReversiError {
     StoneAlreadyPlaced,
     InvalidMove,
     IndexOutOfBound,
     NoStoneToFlip,
-    NextPlayerCantPutStone(Stone),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParsePointError {
+    Empty,
+    InvalidColumn,
+    InvalidRow,
+    OutOfBoard,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParsePositionError {
+    InvalidLength(usize),
+    InvalidSquare(char),
+    InvalidTurn(char),
+    SizeMismatch,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplayErrorKind {
+    Parse(ParsePointError),
+    IllegalMove(ReversiError),
+    UnexpectedPass,
+    WrongPlayer(Stone),
+    GameOver,
+}
+
+/// An error while replaying a move list, with the 1-based ply where it occurred.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReplayError {
+    pub ply: usize,
+    pub kind: ReplayErrorKind,
+}
+
+impl ReplayError {
+    pub fn new(ply: usize, kind: ReplayErrorKind) -> Self {
+        Self { ply, kind }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RecordError {
+    Syntax(usize),
+    NoGame,
+    InvalidProperty(String),
+    UnsupportedBoardSize(usize),
+    Io(std::io::ErrorKind),
+    Replay(ReplayError),
+}
+
+impl From<ReplayError> for RecordError {
+    fn from(error: ReplayError) -> Self {
+        Self::Replay(error)
+    }
+}
+
+impl From<std::io::Error> for RecordError {
+    fn from(error: std::io::Error) -> Self {
+        Self::Io(error.kind())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BookError {
+    /// A malformed line, 1-based.
+    Syntax(usize),
+    Io(std::io::ErrorKind),
+    Replay(ReplayError),
+}
+
+impl From<ReplayError> for BookError {
+    fn from(error: ReplayError) -> Self {
+        Self::Replay(error)
+    }
+}
+
+impl From<std::io::Error> for BookError {
+    fn from(error: std::io::Error) -> Self {
+        Self::Io(error.kind())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WeightsError {
+    Io(std::io::ErrorKind),
+    /// Not a weights file, or one of an unknown version.
+    InvalidFormat,
+    /// The file was saved for other patterns.
+    SizeMismatch,
+}
+
+impl From<std::io::Error> for WeightsError {
+    fn from(error: std::io::Error) -> Self {
+        Self::Io(error.kind())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DatasetError {
+    /// A malformed line, with its 1-based number.
+    Syntax(usize),
+    Io(std::io::ErrorKind),
+}
+
+impl From<std::io::Error> for DatasetError {
+    fn from(error: std::io::Error) -> Self {
+        Self::Io(error.kind())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnapshotError {
+    Position(ParsePositionError),
+    /// The history does not lead to the board, with the 1-based ply of a move
+    /// that doesn't fit.
+    History(usize),
+}
 
-    GameOverWithWinner(Stone),
-    GameOverWithDraw,
+impl From<ParsePositionError> for SnapshotError {
+    fn from(error: ParsePositionError) -> Self {
+        Self::Position(error)
+    }
 }
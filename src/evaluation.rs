@@ -0,0 +1,307 @@
+// Static evaluation, from the point of view of `player`. Higher is better.
+
+use crate::{board::ReversiBoard, point::Point, stability, stone::Stone};
+
+pub const DEFAULT_WEIGHTS: [[i32; 8]; 8] = [
+    [150, -50, 20, 10, 10, 20, -50, 150],
+    [-50, -70, -3, -3, -3, -3, -70, -50],
+    [20, -3, 7, 3, 3, 7, -3, 20],
+    [10, -3, 3, 1, 1, 3, -3, 10],
+    [10, -3, 3, 1, 1, 3, -3, 10],
+    [20, -3, 7, 3, 3, 7, -3, 20],
+    [-50, -70, -3, -3, -3, -3, -70, -50],
+    [150, -50, 20, 10, 10, 20, -50, 150],
+];
+
+const NEIGHBORS: [(isize, isize); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+pub trait Evaluator: Send + Sync {
+    fn evaluate(&self, board: &dyn ReversiBoard, player: Stone) -> i32;
+}
+
+impl<F> Evaluator for F
+where
+    F: Fn(&dyn ReversiBoard, Stone) -> i32 + Send + Sync,
+{
+    fn evaluate(&self, board: &dyn ReversiBoard, player: Stone) -> i32 {
+        self(board, player)
+    }
+}
+
+// +1 for each square where `f` holds for `player`, -1 for the opponent.
+fn difference<F>(board: &dyn ReversiBoard, player: Stone, f: F) -> i32
+where
+    F: Fn(usize, usize, Stone) -> bool,
+{
+    let mut score = 0;
+
+    for y in 0..board.size() {
+        for x in 0..board.size() {
+            if f(x, y, player) {
+                score += 1;
+            }
+            if f(x, y, player.opposite()) {
+                score -= 1;
+            }
+        }
+    }
+
+    score
+}
+
+fn neighbors(board: &dyn ReversiBoard, x: usize, y: usize) -> impl Iterator<Item = Point> + '_ {
+    NEIGHBORS.iter().filter_map(move |&(dx, dy)| {
+        let (nx, ny) = (x.checked_add_signed(dx)?, y.checked_add_signed(dy)?);
+        board.in_range(nx, ny).then(|| Point::new(nx, ny))
+    })
+}
+
+/// Sums a weight for every square, added for own stones and subtracted for
+/// the opponent's. Squares outside the 8x8 table weigh nothing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Positional {
+    weights: [[i32; 8]; 8],
+}
+
+impl Positional {
+    pub fn new() -> Self {
+        Self::with_weights(DEFAULT_WEIGHTS)
+    }
+
+    pub fn with_weights(weights: [[i32; 8]; 8]) -> Self {
+        Self { weights }
+    }
+
+    #[inline]
+    pub fn weights(&self) -> &[[i32; 8]; 8] {
+        &self.weights
+    }
+}
+
+impl Default for Positional {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Evaluator for Positional {
+    fn evaluate(&self, board: &dyn ReversiBoard, player: Stone) -> i32 {
+        let mut score = 0;
+
+        for (y, row) in self.weights.iter().enumerate().take(board.size()) {
+            for (x, weight) in row.iter().enumerate().take(board.size()) {
+                match board.get_at(x, y) {
+                    Some(s) if s == player => score += weight,
+                    Some(_) => score -= weight,
+                    None => {}
+                }
+            }
+        }
+
+        score
+    }
+}
+
+/// The difference in the number of legal moves.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Mobility;
+
+impl Evaluator for Mobility {
+    fn evaluate(&self, board: &dyn ReversiBoard, player: Stone) -> i32 {
+        board.get_can_put_stones(player).len() as i32
+            - board.get_can_put_stones(player.opposite()).len() as i32
+    }
+}
+
+/// The difference in the number of empty squares next to the opponent's
+/// stones, where moves may open up later.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PotentialMobility;
+
+impl Evaluator for PotentialMobility {
+    fn evaluate(&self, board: &dyn ReversiBoard, player: Stone) -> i32 {
+        difference(board, player, |x, y, player| {
+            board.get_at(x, y).is_none()
+                && neighbors(board, x, y).any(|p| board.get_at(p.x, p.y) == Some(player.opposite()))
+        })
+    }
+}
+
+/// The difference in the number of frontier stones, those next to an empty
+/// square. Frontier stones are easy to flip, so fewer is better.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Frontier;
+
+impl Evaluator for Frontier {
+    fn evaluate(&self, board: &dyn ReversiBoard, player: Stone) -> i32 {
+        -difference(board, player, |x, y, player| {
+            board.get_at(x, y) == Some(player)
+                && neighbors(board, x, y).any(|p| board.get_at(p.x, p.y).is_none())
+        })
+    }
+}
+
+/// Rewards corners, and penalizes X-squares (diagonally next to a corner)
+/// and C-squares (next to a corner along an edge) while the corner is empty.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Corners {
+    pub corner: i32,
+    pub x_square: i32,
+    pub c_square: i32,
+}
+
+impl Corners {
+    pub fn new() -> Self {
+        Self {
+            corner: 25,
+            x_square: -12,
+            c_square: -4,
+        }
+    }
+}
+
+impl Default for Corners {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Evaluator for Corners {
+    fn evaluate(&self, board: &dyn ReversiBoard, player: Stone) -> i32 {
+        let n = board.size() - 1;
+        let stone = |x: usize, y: usize| match board.get_at(x, y) {
+            Some(s) if s == player => 1,
+            Some(_) => -1,
+            None => 0,
+        };
+
+        let mut score = 0;
+        for (cx, cy, dx, dy) in [(0, 0, 1, 1), (n, 0, n - 1, 1), (0, n, 1, n - 1), (n, n, n - 1, n - 1)] {
+            if board.get_at(cx, cy).is_some() {
+                score += self.corner * stone(cx, cy);
+                continue;
+            }
+
+            score += self.x_square * stone(dx, dy);
+            score += self.c_square * (stone(dx, cy) + stone(cx, dy));
+        }
+
+        score
+    }
+}
+
+/// The difference in the number of stones.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DiscParity;
+
+impl Evaluator for DiscParity {
+    fn evaluate(&self, board: &dyn ReversiBoard, player: Stone) -> i32 {
+        board.count(player) as i32 - board.count(player.opposite()) as i32
+    }
+}
+
+/// The difference in the number of stable discs, see `stability`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Stability;
+
+impl Evaluator for Stability {
+    fn evaluate(&self, board: &dyn ReversiBoard, player: Stone) -> i32 {
+        let stable = stability::stable_grid(board);
+
+        difference(board, player, |x, y, player| {
+            stable[y][x] && board.get_at(x, y) == Some(player)
+        })
+    }
+}
+
+/// A weighted sum of other evaluators.
+#[derive(Default)]
+pub struct WeightedSum {
+    terms: Vec<(i32, Box<dyn Evaluator>)>,
+}
+
+impl WeightedSum {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `evaluator`, multiplied by `weight`.
+    pub fn with<E: Evaluator + 'static>(mut self, weight: i32, evaluator: E) -> Self {
+        self.terms.push((weight, Box::new(evaluator)));
+        self
+    }
+}
+
+impl Evaluator for WeightedSum {
+    fn evaluate(&self, board: &dyn ReversiBoard, player: Stone) -> i32 {
+        self.terms
+            .iter()
+            .map(|(weight, evaluator)| weight * evaluator.evaluate(board, player))
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{game::SimpleReversiGame, position::Position};
+
+    use super::*;
+
+    #[test]
+    fn start_position_is_even() {
+        let game = SimpleReversiGame::new();
+        let evaluators: [&dyn Evaluator; 7] = [
+            &Positional::new(),
+            &Mobility,
+            &PotentialMobility,
+            &Frontier,
+            &Corners::new(),
+            &DiscParity,
+            &Stability,
+        ];
+
+        for evaluator in evaluators {
+            assert_eq!(evaluator.evaluate(game.board(), Stone::Black), 0);
+        }
+    }
+
+    #[test]
+    fn features() {
+        let game = SimpleReversiGame::from_transcript("f5").unwrap();
+        let board = game.board();
+
+        assert_eq!(DiscParity.evaluate(board, Stone::Black), 3);
+        assert_eq!(Mobility.evaluate(board, Stone::White), 0);
+        assert_eq!(Frontier.evaluate(board, Stone::Black), -3);
+        assert_eq!(
+            PotentialMobility.evaluate(board, Stone::White),
+            -PotentialMobility.evaluate(board, Stone::Black)
+        );
+
+        let position: Position =
+            "X------- -O------ -------- -------- -------- -------- ------O- -------- X"
+                .parse()
+                .unwrap();
+        let board = position.to_board();
+        assert_eq!(Corners::new().evaluate(board.as_ref(), Stone::Black), 25 + 12);
+        assert_eq!(Stability.evaluate(board.as_ref(), Stone::Black), 1);
+    }
+
+    #[test]
+    fn weighted_sum() {
+        let game = SimpleReversiGame::from_transcript("f5").unwrap();
+        let closure = |board: &dyn ReversiBoard, player: Stone| board.count(player) as i32;
+
+        let sum = WeightedSum::new().with(2, DiscParity).with(-1, closure);
+        assert_eq!(sum.evaluate(game.board(), Stone::Black), 2 * 3 - 4);
+    }
+}
@@ -1,7 +1,7 @@
 use std::fmt::Display;
 
 use crate::{
-    board::{ArrayBasedBoard, ReversiBoard}, computer::PlayerType, error::ReversiError, point::Point, stone::Stone
+    board::{ArrayBasedBoard, Board, ReversiBoard}, computer::PlayerType, error::{ReplayError, ReplayErrorKind, ReversiError, SnapshotError}, outcome::{GameResult, MoveOutcome}, point::{Action, Point}, position::Position, stone::Stone, transcript
 };
 
 pub type Result<T> = std::result::Result<T, ReversiError>;
@@ -40,34 +40,323 @@ impl PlayerManager {
     }
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Move {
+    Put {
+        player: Stone,
+        point: Point,
+        flipped: Vec<Point>,
+    },
+    Pass(Stone),
+}
+
+impl Move {
+    pub fn player(&self) -> Stone {
+        match self {
+            Self::Put { player, .. } => *player,
+            Self::Pass(player) => *player,
+        }
+    }
+}
+
+/// The state of a `SimpleReversiGame` as plain data, for persisting games.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GameSnapshot {
+    pub board: Board,
+    pub turn: Stone,
+    pub history: Vec<Move>,
+}
+
 pub struct SimpleReversiGame {
     board: Box<dyn ReversiBoard>,
     turn: Stone,
+    history: Vec<Move>,
+    undone: Vec<Move>,
 }
 
 impl SimpleReversiGame {
     pub fn new() -> Self {
-        let mut board: Box<dyn ReversiBoard> = Box::new(ArrayBasedBoard::new());
+        Self::with_board(Box::new(ArrayBasedBoard::new()))
+    }
+
+    pub fn with_board(mut board: Box<dyn ReversiBoard>) -> Self {
         board.init_four_central_squares();
 
+        Self::with_position(board, Stone::Black)
+    }
+
+    /// Starts a game where `receiver` has `corners` corners, see
+    /// `ReversiBoard::init_with_handicap`, with `first` to move.
+    pub fn with_handicap(corners: usize, receiver: Stone, first: Stone) -> Result<Self> {
+        let mut board: Box<dyn ReversiBoard> = Box::new(ArrayBasedBoard::new());
+        board.init_with_handicap(corners, receiver)?;
+
+        Ok(Self::with_position(board, first))
+    }
+
+    /// Starts a game from `board` as it is, with `turn` to move.
+    pub fn with_position(board: Box<dyn ReversiBoard>, turn: Stone) -> Self {
         Self {
             board,
-            turn: Stone::Black,
+            turn,
+            history: Vec::new(),
+            undone: Vec::new(),
+        }
+    }
+
+    pub fn put_stone(&mut self, x: usize, y: usize) -> Result<MoveOutcome> {
+        let player = self.turn;
+        let opponent_stones = self.stones(player.opposite());
+
+        let outcome = self.board.put_stone(x, y, player)?;
+
+        let flipped = opponent_stones
+            .into_iter()
+            .filter(|p| self.board.get_at(p.x, p.y) == Some(player))
+            .collect();
+
+        self.undone.clear();
+        self.history.push(Move::Put {
+            player,
+            point: Point::new(x, y),
+            flipped,
+        });
+
+        if outcome == MoveOutcome::OpponentPasses {
+            self.history.push(Move::Pass(player.opposite()));
+        } else {
+            self.take_turn();
+        }
+
+        Ok(outcome)
+    }
+
+    /// Takes back the last stone put, along with the passes it caused.
+    /// Returns `false` if there is nothing to undo.
+    pub fn undo(&mut self) -> bool {
+        let Some(index) = self
+            .history
+            .iter()
+            .rposition(|m| matches!(m, Move::Put { .. }))
+        else {
+            return false;
+        };
+
+        let moves: Vec<Move> = self.history.drain(index..).collect();
+        for m in moves.into_iter().rev() {
+            match &m {
+                Move::Put {
+                    player,
+                    point,
+                    flipped,
+                } => {
+                    self.board.set_at(point.x, point.y, None).unwrap();
+                    for p in flipped {
+                        self.board.flip(p.x, p.y).unwrap();
+                    }
+                    self.turn = *player;
+                }
+                Move::Pass(player) => self.turn = *player,
+            }
+
+            self.undone.push(m);
+        }
+
+        true
+    }
+
+    /// Replays the last move taken back by `undo`.
+    /// Returns `false` if there is nothing to redo.
+    pub fn redo(&mut self) -> bool {
+        let Some(m) = self.undone.pop() else {
+            return false;
+        };
+
+        let mut next = Some(m);
+        while let Some(m) = next {
+            match &m {
+                Move::Put {
+                    player,
+                    point,
+                    flipped,
+                } => {
+                    self.board.set_at(point.x, point.y, Some(*player)).unwrap();
+                    for p in flipped {
+                        self.board.flip(p.x, p.y).unwrap();
+                    }
+                }
+                Move::Pass(_) => {}
+            }
+
+            self.turn = m.player().opposite();
+            self.history.push(m);
+
+            next = match self.undone.last() {
+                Some(Move::Pass(_)) => self.undone.pop(),
+                _ => None,
+            };
+        }
+
+        true
+    }
+
+    #[inline]
+    pub fn history(&self) -> &[Move] {
+        &self.history
+    }
+
+    /// The position before the first recorded move, and the player to move in it.
+    pub fn start_position(&self) -> (Box<dyn ReversiBoard>, Stone) {
+        let mut board = dyn_clone::clone_box(self.board.as_ref());
+
+        for m in self.history.iter().rev() {
+            if let Move::Put { point, flipped, .. } = m {
+                board.set_at(point.x, point.y, None).unwrap();
+                for p in flipped {
+                    board.flip(p.x, p.y).unwrap();
+                }
+            }
+        }
+
+        let turn = self.history.first().map_or(self.turn, Move::player);
+
+        (board, turn)
+    }
+
+    pub fn snapshot(&self) -> GameSnapshot {
+        GameSnapshot {
+            board: self.position().cells().clone(),
+            turn: self.turn,
+            history: self.history.clone(),
+        }
+    }
+
+    /// Restores a game, checking that the history leads to the board.
+    pub fn from_snapshot(snapshot: GameSnapshot) -> std::result::Result<Self, SnapshotError> {
+        let position = Position::new(snapshot.board, snapshot.turn)?;
+        Self::check_history(&position, &snapshot.history).map_err(SnapshotError::History)?;
+
+        let mut game = position.to_game();
+        game.history = snapshot.history;
+
+        Ok(game)
+    }
+
+    // Takes the moves back on a copy of the board, then plays them again and
+    // compares. Returns the 1-based ply of a move that doesn't fit.
+    fn check_history(position: &Position, history: &[Move]) -> std::result::Result<(), usize> {
+        let mut board = position.to_board();
+
+        for (i, m) in history.iter().enumerate().rev() {
+            if let Move::Put {
+                player,
+                point,
+                flipped,
+            } = m
+            {
+                let owned = |p: &Point| {
+                    board.in_range(p.x, p.y) && board.get_at(p.x, p.y) == Some(*player)
+                };
+                if !owned(point) || !flipped.iter().all(owned) {
+                    return Err(i + 1);
+                }
+
+                board.set_at(point.x, point.y, None).unwrap();
+                for p in flipped {
+                    board.flip(p.x, p.y).unwrap();
+                }
+            }
+        }
+
+        let turn = history.first().map_or(position.turn(), Move::player);
+        let mut game = Self::with_position(board, turn);
+        for (i, m) in history.iter().enumerate() {
+            if let Move::Put { player, point, .. } = m {
+                if game.turn != *player || game.put_stone(point.x, point.y).is_err() {
+                    return Err(i + 1);
+                }
+            }
+        }
+
+        let plies = game.history.len().max(history.len());
+        if let Some(i) = (0..plies).find(|&i| game.history.get(i) != history.get(i)) {
+            return Err(i + 1);
+        }
+        if game.turn != position.turn() {
+            return Err(history.len());
+        }
+
+        Ok(())
+    }
+
+    pub fn position(&self) -> Position {
+        Position::from_board(self.board.as_ref(), self.turn)
+    }
+
+    pub fn from_transcript(transcript: &str) -> std::result::Result<Self, ReplayError> {
+        let mut game = Self::new();
+        let moves = transcript::parse(transcript, game.board.size())?;
+        game.replay(moves)?;
+
+        Ok(game)
+    }
+
+    pub fn transcript(&self) -> String {
+        transcript::format(&self.history)
+    }
+
+    /// Plays `moves` from the current position. Passes may be given
+    /// explicitly or left out.
+    pub fn replay<I>(&mut self, moves: I) -> std::result::Result<(), ReplayError>
+    where
+        I: IntoIterator<Item = Action>,
+    {
+        let mut pass_pending = false;
+
+        for (i, action) in moves.into_iter().enumerate() {
+            let ply = i + 1;
+
+            match action {
+                Action::Pass if pass_pending => pass_pending = false,
+                Action::Pass if !self.is_game_over() && self.get_can_put_stones().is_empty() => {
+                    self.history.push(Move::Pass(self.turn));
+                    self.take_turn();
+                }
+                Action::Pass => {
+                    return Err(ReplayError::new(ply, ReplayErrorKind::UnexpectedPass));
+                }
+                Action::Put(_) if self.is_game_over() => {
+                    return Err(ReplayError::new(ply, ReplayErrorKind::GameOver));
+                }
+                Action::Put(Point { x, y }) => {
+                    let outcome = self
+                        .put_stone(x, y)
+                        .map_err(|e| ReplayError::new(ply, ReplayErrorKind::IllegalMove(e)))?;
+                    pass_pending = outcome == MoveOutcome::OpponentPasses;
+                }
+            }
         }
+
+        Ok(())
     }
 
-    pub fn put_stone(&mut self, x: usize, y: usize) -> Result<()> {
-        let result = self.board.put_stone(x, y, self.turn);
+    pub fn is_game_over(&self) -> bool {
+        self.board.get_can_put_stones(self.turn).is_empty()
+            && self.board.get_can_put_stones(self.turn.opposite()).is_empty()
+    }
 
-         let Err(ReversiError::NextPlayerCantPutStone(_)) = result else {
-             self.take_turn();
-             return result;
-         };
+    fn stones(&self, player: Stone) -> Vec<Point> {
+        let size = self.board.size();
 
-         result
+        (0..size)
+            .flat_map(|y| (0..size).map(move |x| Point::new(x, y)))
+            .filter(|p| self.board.get_at(p.x, p.y) == Some(player))
+            .collect()
     }
 
-    pub fn winner(&self) -> Result<()> {
+    pub fn winner(&self) -> GameResult {
         self.board.winner()
     }
 
@@ -122,7 +411,7 @@ impl Display for SimpleReversiGame {
 mod tests {
     use std::vec;
 
-    use crate::computer::WeightedComputer;
+    use crate::{bitboard::BitBoard, computer::WeightedComputer, outcome::Score};
 
     use super::*;
 
@@ -145,11 +434,32 @@ mod tests {
         game.board.board_mut()[0][7] = Some(Stone::Black);
 
         let result = game.put_stone(0, 0);
-        assert_eq!(result, Err(ReversiError::GameOverWithWinner(Stone::White)));
+        assert_eq!(
+            result,
+            Ok(MoveOutcome::GameOver {
+                result: GameResult::Winner(Stone::White),
+                score: Score::new(8, size * size - 8),
+            })
+        );
         assert_eq!(game.board().count(Stone::Black), 8);
         assert_eq!(game.board().count(Stone::White), size * size - 8);
     }
 
+    #[test]
+    fn finish_bitboard() {
+        let mut game = SimpleReversiGame::with_board(Box::new(BitBoard::new()));
+
+        *game.board.board_mut() = vec![vec![Some(Stone::White); 8]; 8];
+        game.board.board_mut()[0][0] = None;
+        game.board.board_mut()[0][7] = Some(Stone::Black);
+
+        let result = game.put_stone(0, 0);
+        assert!(result.unwrap().is_game_over());
+        assert_eq!(game.winner(), GameResult::Winner(Stone::White));
+        assert_eq!(game.board().count(Stone::Black), 8);
+        assert_eq!(game.board().count(Stone::White), 56);
+    }
+
     #[test]
     fn cant_put() {
         let mut game = SimpleReversiGame::default();
@@ -162,14 +472,14 @@ mod tests {
 
         assert_eq!(
             game.put_stone(2, 0),
-            Err(ReversiError::NextPlayerCantPutStone(Stone::White))
+            Ok(MoveOutcome::OpponentPasses)
         );
     }
 
     #[test]
     fn cant_put_2() {
         let mut game = SimpleReversiGame::new();
-        let mut player_mgr = PlayerManager::new(
+        let _player_mgr = PlayerManager::new(
             PlayerType::Human,
             PlayerType::Computer(Box::new(WeightedComputer::new(Stone::White)))
         );
@@ -183,7 +493,201 @@ mod tests {
 
         dbg!(&game.board);
         let result = game.put_stone(2, 0);
-        assert_eq!(result, Err(ReversiError::NextPlayerCantPutStone(Stone::White)));
+        assert_eq!(result, Ok(MoveOutcome::OpponentPasses));
+        assert_eq!(game.turn(), Stone::Black);
+    }
+
+    #[test]
+    fn undo_redo() {
+        let mut game = SimpleReversiGame::new();
+        let initial = game.board().board().clone();
+
+        game.put_stone(3, 2).unwrap();
+        let after_first = game.board().board().clone();
+        game.put_stone(2, 4).unwrap();
+        let after_second = game.board().board().clone();
+
+        assert_eq!(
+            game.history(),
+            &[
+                Move::Put {
+                    player: Stone::Black,
+                    point: Point::new(3, 2),
+                    flipped: vec![Point::new(3, 3)],
+                },
+                Move::Put {
+                    player: Stone::White,
+                    point: Point::new(2, 4),
+                    flipped: vec![Point::new(3, 4)],
+                },
+            ]
+        );
+
+        assert!(game.undo());
+        assert_eq!(game.board().board(), &after_first);
+        assert_eq!(game.turn(), Stone::White);
+        assert!(game.undo());
+        assert_eq!(game.board().board(), &initial);
+        assert_eq!(game.turn(), Stone::Black);
+        assert!(!game.undo());
+
+        assert!(game.redo());
+        assert!(game.redo());
+        assert_eq!(game.board().board(), &after_second);
+        assert_eq!(game.turn(), Stone::Black);
+        assert!(!game.redo());
+
+        game.undo();
+        game.put_stone(4, 2).unwrap();
+        assert!(!game.redo());
+        assert_eq!(game.history().len(), 2);
+    }
+
+    #[test]
+    fn invalid_move_is_error() {
+        let mut game = SimpleReversiGame::new();
+        assert_eq!(game.put_stone(0, 0), Err(ReversiError::InvalidMove));
+        assert_eq!(game.turn(), Stone::Black);
+        assert!(game.history().is_empty());
+    }
+
+    #[test]
+    fn transcript_round_trip() {
+        let mut game = SimpleReversiGame::new();
+        let player_mgr = PlayerManager::new(
+            PlayerType::Computer(Box::new(WeightedComputer::new(Stone::Black))),
+            PlayerType::Computer(Box::new(WeightedComputer::new(Stone::White))),
+        );
+        while !game.is_game_over() {
+            let Point { x, y } = player_mgr.decide(game.board(), game.turn()).unwrap();
+            game.put_stone(x, y).unwrap();
+        }
+
+        let transcript = game.transcript();
+        assert!(transcript.starts_with("d3"));
+
+        let replayed = SimpleReversiGame::from_transcript(&transcript).unwrap();
+        assert_eq!(replayed.board().board(), game.board().board());
+        assert_eq!(replayed.history(), game.history());
+        assert_eq!(replayed.transcript(), transcript);
+    }
+
+    #[test]
+    fn transcript_illegal_move() {
+        assert_eq!(
+            SimpleReversiGame::from_transcript("f5d6c3d3c4f4f6f3a1").err(),
+            Some(ReplayError::new(
+                9,
+                ReplayErrorKind::IllegalMove(ReversiError::InvalidMove)
+            ))
+        );
+        assert_eq!(
+            SimpleReversiGame::from_transcript("f5pass").err(),
+            Some(ReplayError::new(2, ReplayErrorKind::UnexpectedPass))
+        );
+    }
+
+    #[test]
+    fn transcript_with_pass() {
+        let mut game = SimpleReversiGame::new();
+        *game.board.board_mut() = vec![vec![None; 8]; 8];
+        game.board.board_mut()[0][0] = Some(Stone::Black);
+        game.board.board_mut()[0][1] = Some(Stone::White);
+        game.board.board_mut()[7][7] = Some(Stone::Black);
+        game.board.board_mut()[7][6] = Some(Stone::White);
+
+        game.replay(transcript::parse("c1 pass f8", 8).unwrap()).unwrap();
+        assert!(game.is_game_over());
+        assert_eq!(game.transcript(), "c1f8");
+        assert_eq!(
+            game.replay([Action::Put(Point::new(3, 3))]),
+            Err(ReplayError::new(1, ReplayErrorKind::GameOver))
+        );
+    }
+
+    #[test]
+    fn snapshot() {
+        let game = SimpleReversiGame::from_transcript("f5d6c3d3c4").unwrap();
+        let mut restored = SimpleReversiGame::from_snapshot(game.snapshot()).unwrap();
+
+        assert_eq!(restored.position(), game.position());
+        assert_eq!(restored.history(), game.history());
+        assert!(restored.undo());
+        assert_eq!(restored.transcript(), "f5d6c3d3");
+
+        let mut snapshot = game.snapshot();
+        snapshot.board.pop();
+        assert!(SimpleReversiGame::from_snapshot(snapshot).is_err());
+    }
+
+    #[test]
+    fn snapshot_with_wrong_history() {
+        let game = SimpleReversiGame::from_transcript("f5d6c3d3c4").unwrap();
+        let restore = |history: Vec<Move>| {
+            let mut snapshot = game.snapshot();
+            snapshot.history = history;
+            SimpleReversiGame::from_snapshot(snapshot).err()
+        };
+
+        // A move of the other game.
+        let other = SimpleReversiGame::from_transcript("f5d6c3d3c4f4").unwrap();
+        assert_eq!(restore(other.history().to_vec()), Some(SnapshotError::History(6)));
+
+        let mut history = game.history().to_vec();
+        if let Move::Put { flipped, .. } = &mut history[2] {
+            flipped.pop();
+        }
+        assert!(matches!(restore(history), Some(SnapshotError::History(_))));
+
+        let mut history = game.history().to_vec();
+        if let Move::Put { point, .. } = &mut history[4] {
+            *point = Point::new(100, 0);
+        }
+        assert_eq!(restore(history), Some(SnapshotError::History(5)));
+
+        let mut history = game.history().to_vec();
+        history.remove(2);
+        assert!(matches!(restore(history), Some(SnapshotError::History(_))));
+
+        // A history may start after the start of the game.
+        assert_eq!(restore(game.history()[2..].to_vec()), None);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn snapshot_json() {
+        let game = SimpleReversiGame::from_transcript("f5d6c3").unwrap();
+        let json = serde_json::to_string(&game.snapshot()).unwrap();
+        let snapshot: GameSnapshot = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(snapshot, game.snapshot());
+        assert_eq!(
+            SimpleReversiGame::from_snapshot(snapshot).unwrap().transcript(),
+            "f5d6c3"
+        );
+    }
+
+    #[test]
+    fn undo_pass() {
+        let mut game = SimpleReversiGame::with_board(Box::new(BitBoard::new()));
+        *game.board.board_mut() = vec![vec![None; 8]; 8];
+        game.board.board_mut()[0][0] = Some(Stone::Black);
+        game.board.board_mut()[0][1] = Some(Stone::White);
+        game.board.board_mut()[7][7] = Some(Stone::Black);
+        game.board.board_mut()[7][6] = Some(Stone::White);
+        let initial = game.board().board().clone();
+
+        let _ = game.put_stone(2, 0);
+        assert_eq!(game.history().last(), Some(&Move::Pass(Stone::White)));
+        assert_eq!(game.turn(), Stone::Black);
+
+        assert!(game.undo());
+        assert!(game.history().is_empty());
+        assert_eq!(game.board().board(), &initial);
+        assert_eq!(game.turn(), Stone::Black);
+
+        assert!(game.redo());
+        assert_eq!(game.history().len(), 2);
         assert_eq!(game.turn(), Stone::Black);
     }
 }
@@ -1,8 +1,27 @@
 // Reversi
 
+pub mod background;
+pub mod bitboard;
 pub mod board;
+pub mod book;
+pub mod clock;
 pub mod computer;
+pub mod difficulty;
+pub mod endgame;
+pub mod error;
+pub mod evaluation;
 pub mod game;
+pub mod mcts;
+pub mod outcome;
+pub mod pattern;
 pub mod point;
+pub mod position;
+pub mod record;
+pub mod selfplay;
+pub mod stability;
 pub mod stone;
-pub mod error;
+pub mod symmetry;
+pub mod tournament;
+pub mod transcript;
+pub mod transposition;
+pub mod zobrist;
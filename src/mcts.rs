@@ -0,0 +1,379 @@
+// Monte Carlo tree search with UCT selection.
+
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use rand::{seq::SliceRandom, RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::{
+    background::{CancelToken, Progress},
+    board::ReversiBoard,
+    computer::{Computer, WeightedComputer},
+    outcome::GameResult,
+    point::Point,
+    stone::Stone,
+};
+
+const DEFAULT_EXPLORATION: f64 = std::f64::consts::SQRT_2;
+const ITERATIONS_PER_REPORT: usize = 256;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Budget {
+    Iterations(usize),
+    Time(Duration),
+}
+
+/// Picks moves while playing out a game from a new node.
+pub trait RolloutPolicy: Send + Sync {
+    /// `moves` are the legal moves of `player` on `board`, never empty.
+    fn choose(
+        &self,
+        board: &dyn ReversiBoard,
+        player: Stone,
+        moves: &[Point],
+        rng: &mut dyn RngCore,
+    ) -> Point;
+}
+
+/// Chooses uniformly among the legal moves.
+pub struct UniformRollout;
+
+impl RolloutPolicy for UniformRollout {
+    fn choose(
+        &self,
+        _board: &dyn ReversiBoard,
+        _player: Stone,
+        moves: &[Point],
+        rng: &mut dyn RngCore,
+    ) -> Point {
+        *moves.choose(rng).unwrap()
+    }
+}
+
+impl RolloutPolicy for WeightedComputer {
+    fn choose(
+        &self,
+        board: &dyn ReversiBoard,
+        player: Stone,
+        _moves: &[Point],
+        _rng: &mut dyn RngCore,
+    ) -> Point {
+        self.best_move(board, player)
+    }
+}
+
+struct Node<'a> {
+    board: Box<dyn ReversiBoard + 'a>,
+    /// The player to move on `board`.
+    player: Stone,
+    /// The move that led here, None for a pass.
+    action: Option<Point>,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    untried: Vec<Option<Point>>,
+    visits: u32,
+    /// Wins of the player who moved into this node, draws counting half.
+    wins: f64,
+}
+
+impl<'a> Node<'a> {
+    fn new(
+        board: Box<dyn ReversiBoard + 'a>,
+        player: Stone,
+        action: Option<Point>,
+        parent: Option<usize>,
+    ) -> Self {
+        let moves = board.get_can_put_stones(player);
+        let untried = if !moves.is_empty() {
+            moves.into_iter().map(Some).collect()
+        } else if board.get_can_put_stones(player.opposite()).is_empty() {
+            Vec::new()
+        } else {
+            vec![None]
+        };
+
+        Self {
+            board,
+            player,
+            action,
+            parent,
+            children: Vec::new(),
+            untried,
+            visits: 0,
+            wins: 0.0,
+        }
+    }
+
+    fn uct(&self, parent_visits: u32, exploration: f64) -> f64 {
+        let visits = self.visits as f64;
+        self.wins / visits + exploration * ((parent_visits as f64).ln() / visits).sqrt()
+    }
+}
+
+pub struct MctsComputer {
+    color: Stone,
+    budget: Budget,
+    exploration: f64,
+    policy: Box<dyn RolloutPolicy>,
+    seed: u64,
+    rng: Mutex<ChaCha8Rng>,
+}
+
+impl MctsComputer {
+    pub fn new(color: Stone, budget: Budget) -> Self {
+        Self::with_seed(color, budget, rand::random())
+    }
+
+    /// Time budgets still depend on how many iterations fit in the time, see
+    /// `Computer::seed`.
+    pub fn with_seed(color: Stone, budget: Budget, seed: u64) -> Self {
+        Self {
+            color,
+            budget,
+            exploration: DEFAULT_EXPLORATION,
+            policy: Box::new(UniformRollout),
+            seed,
+            rng: Mutex::new(ChaCha8Rng::seed_from_u64(seed)),
+        }
+    }
+
+    #[inline]
+    pub fn budget(&self) -> Budget {
+        self.budget
+    }
+
+    pub fn set_budget(&mut self, budget: Budget) {
+        self.budget = budget;
+    }
+
+    /// Sets the UCT exploration constant, √2 by default.
+    pub fn set_exploration(&mut self, exploration: f64) {
+        self.exploration = exploration;
+    }
+
+    pub fn set_rollout_policy(&mut self, policy: Box<dyn RolloutPolicy>) {
+        self.policy = policy;
+    }
+
+    fn select(&self, tree: &[Node]) -> usize {
+        let mut index = 0;
+
+        while tree[index].untried.is_empty() && !tree[index].children.is_empty() {
+            let parent_visits = tree[index].visits;
+            index = *tree[index]
+                .children
+                .iter()
+                .max_by(|&&a, &&b| {
+                    let a = tree[a].uct(parent_visits, self.exploration);
+                    let b = tree[b].uct(parent_visits, self.exploration);
+                    a.total_cmp(&b)
+                })
+                .unwrap();
+        }
+
+        index
+    }
+
+    fn expand(&self, tree: &mut Vec<Node<'_>>, index: usize, rng: &mut ChaCha8Rng) -> usize {
+        let untried = &mut tree[index].untried;
+        if untried.is_empty() {
+            return index;
+        }
+
+        let i = (rng.next_u32() as usize) % untried.len();
+        let action = untried.swap_remove(i);
+
+        let node = &tree[index];
+        let mut board = dyn_clone::clone_box(node.board.as_ref());
+        if let Some(p) = action {
+            let _ = board.put_stone(p.x, p.y, node.player);
+        }
+
+        let child = Node::new(board, node.player.opposite(), action, Some(index));
+        tree.push(child);
+
+        let child = tree.len() - 1;
+        tree[index].children.push(child);
+        child
+    }
+
+    fn rollout(&self, node: &Node, rng: &mut ChaCha8Rng) -> GameResult {
+        let mut board = dyn_clone::clone_box(node.board.as_ref());
+        let mut player = node.player;
+
+        let mut passed = false;
+
+        loop {
+            let moves = board.get_can_put_stones(player);
+            if moves.is_empty() {
+                if passed {
+                    break;
+                }
+                passed = true;
+            } else {
+                let p = self.policy.choose(board.as_ref(), player, &moves, rng);
+                let _ = board.put_stone(p.x, p.y, player);
+                passed = false;
+            }
+            player = player.opposite();
+        }
+
+        board.winner()
+    }
+
+    fn backpropagate(&self, tree: &mut [Node], mut index: usize, result: GameResult) {
+        loop {
+            let node = &mut tree[index];
+            node.visits += 1;
+            node.wins += match result {
+                GameResult::Winner(winner) if winner == node.player.opposite() => 1.0,
+                GameResult::Winner(_) => 0.0,
+                GameResult::Draw => 0.5,
+            };
+
+            match node.parent {
+                Some(parent) => index = parent,
+                None => break,
+            }
+        }
+    }
+}
+
+impl MctsComputer {
+    // The most visited child of `index`.
+    fn most_visited(tree: &[Node], index: usize) -> Option<usize> {
+        tree[index]
+            .children
+            .iter()
+            .copied()
+            .max_by_key(|&child| tree[child].visits)
+    }
+
+    fn progress(tree: &[Node], iterations: u64) -> Progress {
+        let best = Self::most_visited(tree, 0).unwrap();
+
+        let mut depth = 1;
+        let mut index = best;
+        while let Some(child) = Self::most_visited(tree, index) {
+            depth += 1;
+            index = child;
+        }
+
+        Progress {
+            depth,
+            best_move: tree[best].action.expect("no legal move to decide"),
+            nodes: iterations,
+        }
+    }
+
+    fn search(
+        &self,
+        board: &dyn ReversiBoard,
+        budget: Budget,
+        token: Option<&CancelToken>,
+        report: &mut dyn FnMut(Progress),
+    ) -> Point {
+        let root = Node::new(dyn_clone::clone_box(board), self.color, None, None);
+        if let [Some(p)] = root.untried[..] {
+            return p;
+        }
+
+        let mut rng = self.rng.lock().unwrap();
+        let mut tree = vec![root];
+        let start = Instant::now();
+        let mut iterations = 0;
+
+        loop {
+            let done = match budget {
+                Budget::Iterations(n) => iterations >= n,
+                Budget::Time(limit) => start.elapsed() >= limit,
+            };
+            let cancelled = token.is_some_and(|token| token.is_cancelled());
+            if (done || cancelled) && iterations > 0 {
+                break;
+            }
+
+            let selected = self.select(&tree);
+            let expanded = self.expand(&mut tree, selected, &mut rng);
+            let result = self.rollout(&tree[expanded], &mut rng);
+            self.backpropagate(&mut tree, expanded, result);
+
+            iterations += 1;
+            if iterations % ITERATIONS_PER_REPORT == 0 {
+                report(Self::progress(&tree, iterations as u64));
+            }
+        }
+
+        Self::progress(&tree, iterations as u64).best_move
+    }
+}
+
+impl Computer for MctsComputer {
+    fn decide(&self, board: &dyn ReversiBoard) -> Point {
+        self.search(board, self.budget, None, &mut |_| {})
+    }
+
+    fn decide_within(&self, board: &dyn ReversiBoard, limit: Duration) -> Point {
+        self.search(board, Budget::Time(limit), None, &mut |_| {})
+    }
+
+    fn decide_with(
+        &self,
+        board: &dyn ReversiBoard,
+        token: &CancelToken,
+        report: &mut dyn FnMut(Progress),
+    ) -> Point {
+        self.search(board, self.budget, Some(token), report)
+    }
+
+    fn seed(&self) -> Option<u64> {
+        Some(self.seed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{endgame, game::SimpleReversiGame};
+
+    use super::*;
+
+    #[test]
+    fn same_seed_same_move() {
+        let game = SimpleReversiGame::from_transcript("f5d6c3d3c4f4").unwrap();
+
+        let decide = |seed| {
+            MctsComputer::with_seed(Stone::White, Budget::Iterations(200), seed)
+                .decide(game.board())
+        };
+        let p = decide(7);
+
+        assert!(game.board().check_can_put(p.x, p.y, Stone::White));
+        assert_eq!(decide(7), p);
+    }
+
+    #[test]
+    fn finds_winning_endgame_move() {
+        let mut game = SimpleReversiGame::new();
+        let weighted = WeightedComputer::new(Stone::Black);
+        while endgame::empties(game.board()) > 8 {
+            let p = weighted.best_move(game.board(), game.turn());
+            game.put_stone(p.x, p.y).unwrap();
+        }
+
+        let player = game.turn();
+        let best = endgame::solve(game.board(), player).score;
+
+        let mut mcts = MctsComputer::with_seed(player, Budget::Iterations(3000), 1);
+        mcts.set_rollout_policy(Box::new(WeightedComputer::new(player)));
+        let p = mcts.decide(game.board());
+
+        let mut board = dyn_clone::clone_box(game.board());
+        board.put_stone(p.x, p.y, player).unwrap();
+        let score = -endgame::solve(board.as_ref(), player.opposite()).score;
+
+        assert_eq!(score.signum(), best.signum());
+    }
+}
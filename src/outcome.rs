@@ -0,0 +1,59 @@
+use crate::stone::Stone;
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameResult {
+    Winner(Stone),
+    Draw,
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Score {
+    pub black: usize,
+    pub white: usize,
+}
+
+impl Score {
+    pub fn new(black: usize, white: usize) -> Self {
+        Self { black, white }
+    }
+
+    pub fn get(&self, player: Stone) -> usize {
+        match player {
+            Stone::Black => self.black,
+            Stone::White => self.white,
+        }
+    }
+
+    /// Disc differential from `player`'s point of view.
+    pub fn difference(&self, player: Stone) -> i32 {
+        self.get(player) as i32 - self.get(player.opposite()) as i32
+    }
+
+    pub fn result(&self) -> GameResult {
+        match self.black.cmp(&self.white) {
+            std::cmp::Ordering::Greater => GameResult::Winner(Stone::Black),
+            std::cmp::Ordering::Less => GameResult::Winner(Stone::White),
+            std::cmp::Ordering::Equal => GameResult::Draw,
+        }
+    }
+}
+
+/// What happens after a stone has been put successfully.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoveOutcome {
+    /// The opponent moves next.
+    Continue,
+    /// The opponent has no legal move, so the same player moves again.
+    OpponentPasses,
+    /// Neither player can move.
+    GameOver { result: GameResult, score: Score },
+}
+
+impl MoveOutcome {
+    pub fn is_game_over(&self) -> bool {
+        matches!(self, Self::GameOver { .. })
+    }
+}
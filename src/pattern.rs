@@ -0,0 +1,362 @@
+// Pattern-based evaluation in the style of Logistello, for 8x8 boards.
+//
+// Weights files are little-endian: "RVPW", the version, the number of phases
+// and of tables and each table size as u32, then the weights as f32.
+
+use std::io::{Read, Write};
+
+use crate::{
+    board::{ReversiBoard, DEFAULT_BOARD_SIZE},
+    error::WeightsError,
+    evaluation::Evaluator,
+    point::Point,
+    position::Position,
+    stone::Stone,
+    symmetry::Symmetry,
+};
+
+const MAGIC: &[u8; 4] = b"RVPW";
+const VERSION: u32 = 1;
+const DEFAULT_PHASES: usize = 10;
+const MAX_PHASES: usize = 64;
+const SQUARES: usize = DEFAULT_BOARD_SIZE * DEFAULT_BOARD_SIZE;
+
+// Weights are in discs, evaluations in hundredths of a disc.
+const SCALE: f32 = 100.0;
+
+fn base_patterns() -> Vec<Vec<Point>> {
+    let line = |y: usize| (0..8).map(|x| Point::new(x, y)).collect();
+    let diagonal = |offset: usize| (0..8 - offset).map(|i| Point::new(i, i + offset)).collect();
+    let block = |width: usize, height: usize| {
+        (0..height)
+            .flat_map(|y| (0..width).map(move |x| Point::new(x, y)))
+            .collect()
+    };
+
+    let mut edge: Vec<Point> = line(0);
+    edge.extend([Point::new(1, 1), Point::new(6, 1)]);
+
+    vec![
+        edge,
+        block(3, 3),
+        block(5, 2),
+        diagonal(0),
+        diagonal(1),
+        diagonal(2),
+        diagonal(3),
+        diagonal(4),
+        line(1),
+        line(2),
+        line(3),
+    ]
+}
+
+// A pattern placed on the board, with the table its weights are in.
+struct Instance {
+    squares: Vec<Point>,
+    table: usize,
+}
+
+// Every distinct image of every base pattern under the symmetries.
+fn instances(patterns: &[Vec<Point>]) -> Vec<Instance> {
+    let mut result: Vec<Instance> = Vec::new();
+    let mut seen: Vec<Vec<Point>> = Vec::new();
+
+    for (table, pattern) in patterns.iter().enumerate() {
+        for symmetry in Symmetry::ALL {
+            let squares: Vec<Point> = pattern
+                .iter()
+                .map(|&p| symmetry.apply(p, DEFAULT_BOARD_SIZE))
+                .collect();
+
+            let mut set = squares.clone();
+            set.sort_by_key(|p| (p.y, p.x));
+            if seen.contains(&set) {
+                continue;
+            }
+
+            seen.push(set);
+            result.push(Instance { squares, table });
+        }
+    }
+
+    result
+}
+
+pub struct PatternEvaluator {
+    instances: Vec<Instance>,
+    /// `weights[phase][table][index]`
+    weights: Vec<Vec<Vec<f32>>>,
+}
+
+impl PatternEvaluator {
+    /// Creates an evaluator with all weights zero.
+    pub fn new() -> Self {
+        Self::with_phases(DEFAULT_PHASES)
+    }
+
+    /// Creates an evaluator with `phases` sets of weights, each used for an
+    /// equal range of disc counts.
+    pub fn with_phases(phases: usize) -> Self {
+        let patterns = base_patterns();
+        let tables: Vec<Vec<f32>> = patterns
+            .iter()
+            .map(|pattern| vec![0.0; 3usize.pow(pattern.len() as u32)])
+            .collect();
+
+        Self {
+            instances: instances(&patterns),
+            weights: vec![tables; phases.max(1)],
+        }
+    }
+
+    #[inline]
+    pub fn phases(&self) -> usize {
+        self.weights.len()
+    }
+
+    fn phase(&self, board: &dyn ReversiBoard) -> usize {
+        let discs = board.count(Stone::Black) + board.count(Stone::White);
+        let phase = discs.saturating_sub(4) * self.phases() / (SQUARES - 3);
+
+        phase.min(self.phases() - 1)
+    }
+
+    // The index of the arrangement in `instance`: a base-3 number with a
+    // digit per square, 0 for empty, 1 for `player` and 2 for the opponent.
+    fn index(board: &dyn ReversiBoard, instance: &Instance, player: Stone) -> usize {
+        instance.squares.iter().rev().fold(0, |index, p| {
+            let digit = match board.get_at(p.x, p.y) {
+                None => 0,
+                Some(s) if s == player => 1,
+                Some(_) => 2,
+            };
+            index * 3 + digit
+        })
+    }
+
+    // The weights that apply to the position, as (table, index) pairs.
+    fn features(&self, board: &dyn ReversiBoard, player: Stone) -> Vec<(usize, usize)> {
+        self.instances
+            .iter()
+            .map(|instance| (instance.table, Self::index(board, instance, player)))
+            .collect()
+    }
+
+    fn predict(&self, phase: usize, features: &[(usize, usize)]) -> f32 {
+        features
+            .iter()
+            .map(|&(table, index)| self.weights[phase][table][index])
+            .sum()
+    }
+
+    /// Fits the weights to `samples` by least squares, with `epochs` passes
+    /// of gradient descent. Each sample is a position and its final disc
+    /// differential for the player to move. Returns the mean squared error
+    /// of the last pass.
+    pub fn train(&mut self, samples: &[(Position, f32)], epochs: usize, learning_rate: f32) -> f32 {
+        let samples: Vec<_> = samples
+            .iter()
+            .filter(|(position, _)| position.size() == DEFAULT_BOARD_SIZE)
+            .map(|(position, score)| {
+                let board = position.to_board();
+                let phase = self.phase(board.as_ref());
+                (phase, self.features(board.as_ref(), position.turn()), *score)
+            })
+            .collect();
+        if samples.is_empty() {
+            return 0.0;
+        }
+
+        // Each sample updates one weight per instance, so scale the step to
+        // keep the total change in the prediction at `learning_rate`.
+        let step = learning_rate / self.instances.len() as f32;
+        let mut error = 0.0;
+
+        for _ in 0..epochs {
+            error = 0.0;
+
+            for (phase, features, score) in &samples {
+                let residual = score - self.predict(*phase, features);
+                error += residual * residual;
+
+                for &(table, index) in features {
+                    self.weights[*phase][table][index] += step * residual;
+                }
+            }
+
+            error /= samples.len() as f32;
+        }
+
+        error
+    }
+
+    pub fn read<R: Read>(reader: &mut R) -> Result<Self, WeightsError> {
+        let mut magic = [0; 4];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC || read_u32(reader)? != VERSION {
+            return Err(WeightsError::InvalidFormat);
+        }
+
+        // Check the header before allocating anything from it.
+        let phases = read_u32(reader)? as usize;
+        if phases == 0 || phases > MAX_PHASES {
+            return Err(WeightsError::InvalidFormat);
+        }
+        let patterns = base_patterns();
+        if read_u32(reader)? as usize != patterns.len() {
+            return Err(WeightsError::SizeMismatch);
+        }
+        for pattern in &patterns {
+            if read_u32(reader)? as usize != 3usize.pow(pattern.len() as u32) {
+                return Err(WeightsError::SizeMismatch);
+            }
+        }
+
+        let mut evaluator = Self::with_phases(phases);
+
+        let mut bytes = [0; 4];
+        for weight in evaluator.weights.iter_mut().flatten().flatten() {
+            reader.read_exact(&mut bytes)?;
+            *weight = f32::from_le_bytes(bytes);
+        }
+
+        Ok(evaluator)
+    }
+
+    pub fn write<W: Write>(&self, writer: &mut W) -> Result<(), WeightsError> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&VERSION.to_le_bytes())?;
+        writer.write_all(&(self.phases() as u32).to_le_bytes())?;
+        writer.write_all(&(self.weights[0].len() as u32).to_le_bytes())?;
+        for table in &self.weights[0] {
+            writer.write_all(&(table.len() as u32).to_le_bytes())?;
+        }
+
+        for weight in self.weights.iter().flatten().flatten() {
+            writer.write_all(&weight.to_le_bytes())?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for PatternEvaluator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Evaluator for PatternEvaluator {
+    /// In hundredths of a disc. Other board sizes than 8x8 always score 0.
+    fn evaluate(&self, board: &dyn ReversiBoard, player: Stone) -> i32 {
+        if board.size() != DEFAULT_BOARD_SIZE {
+            return 0;
+        }
+
+        let phase = self.phase(board);
+        let score = self.predict(phase, &self.features(board, player));
+
+        (score * SCALE).round() as i32
+    }
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> Result<u32, WeightsError> {
+    let mut bytes = [0; 4];
+    reader.read_exact(&mut bytes)?;
+
+    Ok(u32::from_le_bytes(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+
+    use crate::game::SimpleReversiGame;
+
+    use super::*;
+
+    fn samples() -> Vec<(Position, f32)> {
+        let mut rng = StdRng::seed_from_u64(21);
+        let mut samples = Vec::new();
+
+        for _ in 0..20 {
+            let mut game = SimpleReversiGame::new();
+            while !game.is_game_over() {
+                let moves = game.get_can_put_stones();
+                let Some(p) = moves.choose(&mut rng) else {
+                    break;
+                };
+                game.put_stone(p.x, p.y).unwrap();
+
+                let position = game.position();
+                let score = game.board().count(position.turn()) as f32
+                    - game.board().count(position.turn().opposite()) as f32;
+                samples.push((position, score));
+            }
+        }
+
+        samples
+    }
+
+    #[test]
+    fn instances_cover_the_board() {
+        let evaluator = PatternEvaluator::new();
+        assert_eq!(evaluator.instances.len(), 46);
+
+        for y in 0..8 {
+            for x in 0..8 {
+                let p = Point::new(x, y);
+                assert!(evaluator.instances.iter().any(|i| i.squares.contains(&p)));
+            }
+        }
+
+        let game = SimpleReversiGame::new();
+        assert_eq!(evaluator.evaluate(game.board(), Stone::Black), 0);
+    }
+
+    #[test]
+    fn train_disc_difference() {
+        let samples = samples();
+        let mut evaluator = PatternEvaluator::with_phases(2);
+
+        let first = evaluator.train(&samples, 1, 0.5);
+        let last = evaluator.train(&samples, 20, 0.5);
+        assert!(last < first / 10.0, "{} {}", first, last);
+
+        let (position, score) = &samples[30];
+        let board = position.to_board();
+        let evaluation = evaluator.evaluate(board.as_ref(), position.turn());
+        assert!((evaluation as f32 / SCALE - score).abs() < 2.0);
+    }
+
+    #[test]
+    fn read_write() {
+        let mut evaluator = PatternEvaluator::with_phases(2);
+        evaluator.train(&samples()[..50], 2, 0.5);
+
+        let mut bytes = Vec::new();
+        evaluator.write(&mut bytes).unwrap();
+        let read = PatternEvaluator::read(&mut bytes.as_slice()).unwrap();
+        assert_eq!(read.weights, evaluator.weights);
+
+        bytes[0] = b'X';
+        assert_eq!(
+            PatternEvaluator::read(&mut bytes.as_slice()).err(),
+            Some(WeightsError::InvalidFormat)
+        );
+
+        let mut header = MAGIC.to_vec();
+        header.extend(VERSION.to_le_bytes());
+        header.extend(u32::MAX.to_le_bytes());
+        assert_eq!(
+            PatternEvaluator::read(&mut header.as_slice()).err(),
+            Some(WeightsError::InvalidFormat)
+        );
+
+        assert_eq!(
+            PatternEvaluator::read(&mut &bytes[..2]).err(),
+            Some(WeightsError::Io(std::io::ErrorKind::UnexpectedEof))
+        );
+    }
+}
@@ -1,3 +1,10 @@
+use std::{fmt::Display, str::FromStr};
+
+use crate::error::ParsePointError;
+
+const LETTERS: usize = 26;
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Point {
     pub x: usize,
@@ -8,4 +15,182 @@ impl Point {
     pub fn new(x: usize, y: usize) -> Point {
         Point { x, y }
     }
+
+    /// Parses a coordinate such as "d3" and checks that it is on a board of `size`.
+    pub fn parse_on(s: &str, size: usize) -> Result<Point, ParsePointError> {
+        let point: Point = s.parse()?;
+
+        if point.x >= size || point.y >= size {
+            return Err(ParsePointError::OutOfBoard);
+        }
+
+        Ok(point)
+    }
+}
+
+/// Columns past z, off any board, are written as aa, ab, and so on.
+impl Display for Point {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut column = Vec::new();
+        let mut x = self.x;
+        loop {
+            column.push((b'a' + (x % LETTERS) as u8) as char);
+            if x < LETTERS {
+                break;
+            }
+            x = x / LETTERS - 1;
+        }
+
+        let column: String = column.into_iter().rev().collect();
+        write!(f, "{}{}", column, self.y + 1)
+    }
+}
+
+impl FromStr for Point {
+    type Err = ParsePointError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+
+        if s.is_empty() {
+            return Err(ParsePointError::Empty);
+        }
+
+        let letters = s.find(|c: char| !c.is_ascii_alphabetic()).unwrap_or(s.len());
+        if letters == 0 {
+            return Err(ParsePointError::InvalidColumn);
+        }
+        let x = s[..letters]
+            .bytes()
+            .try_fold(0usize, |x, b| {
+                let digit = (b.to_ascii_lowercase() - b'a') as usize + 1;
+                x.checked_mul(LETTERS)?.checked_add(digit)
+            })
+            .ok_or(ParsePointError::InvalidColumn)?
+            - 1;
+
+        let row = &s[letters..];
+        if row.is_empty() || !row.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(ParsePointError::InvalidRow);
+        }
+        let Ok(y) = row.parse::<usize>() else {
+            return Err(ParsePointError::InvalidRow);
+        };
+        if y == 0 {
+            return Err(ParsePointError::InvalidRow);
+        }
+
+        Ok(Point::new(x, y - 1))
+    }
+}
+
+/// A move as written in game notation: a coordinate or a pass.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Put(Point),
+    Pass,
+}
+
+impl Action {
+    pub const PASS: &'static str = "pass";
+
+    pub fn parse_on(s: &str, size: usize) -> Result<Action, ParsePointError> {
+        match s.parse()? {
+            Action::Put(point) if point.x >= size || point.y >= size => {
+                Err(ParsePointError::OutOfBoard)
+            }
+            action => Ok(action),
+        }
+    }
+}
+
+impl Display for Action {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Put(point) => write!(f, "{}", point),
+            Self::Pass => write!(f, "{}", Self::PASS),
+        }
+    }
+}
+
+impl FromStr for Action {
+    type Err = ParsePointError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+
+        if s.eq_ignore_ascii_case(Self::PASS) || s.eq_ignore_ascii_case("pa") || s == "--" {
+            return Ok(Action::Pass);
+        }
+
+        s.parse().map(Action::Put)
+    }
+}
+
+impl From<Point> for Action {
+    fn from(point: Point) -> Self {
+        Action::Put(point)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        assert_eq!("d3".parse(), Ok(Point::new(3, 2)));
+        assert_eq!("F5".parse(), Ok(Point::new(5, 4)));
+        assert_eq!(" a1 ".parse(), Ok(Point::new(0, 0)));
+        assert_eq!("".parse::<Point>(), Err(ParsePointError::Empty));
+        assert_eq!("a0".parse::<Point>(), Err(ParsePointError::InvalidRow));
+        assert_eq!("d 3".parse::<Point>(), Err(ParsePointError::InvalidRow));
+        assert_eq!("33".parse::<Point>(), Err(ParsePointError::InvalidColumn));
+    }
+
+    #[test]
+    fn parse_on_board() {
+        assert_eq!(Point::parse_on("h8", 8), Ok(Point::new(7, 7)));
+        assert_eq!(Point::parse_on("i1", 8), Err(ParsePointError::OutOfBoard));
+        assert_eq!(Point::parse_on("a9", 8), Err(ParsePointError::OutOfBoard));
+        assert_eq!(Point::parse_on("j10", 10), Ok(Point::new(9, 9)));
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(Point::new(3, 2).to_string(), "d3");
+        assert_eq!(Point::new(9, 9).to_string(), "j10");
+        for s in ["a1", "h8", "e6"] {
+            assert_eq!(s.parse::<Point>().unwrap().to_string(), s);
+        }
+
+        let size = crate::board::MAX_BOARD_SIZE;
+        for y in 0..size {
+            for x in 0..size {
+                let p = Point::new(x, y);
+                assert_eq!(Point::parse_on(&p.to_string(), size), Ok(p));
+            }
+        }
+        assert_eq!(Point::new(25, 25).to_string(), "z26");
+
+        // Past the largest board.
+        assert_eq!(Point::new(26, 0).to_string(), "aa1");
+        assert_eq!(Point::new(27 * 26, 0).to_string(), "aaa1");
+        assert!(Point::new(usize::MAX, 0).to_string().ends_with('1'));
+        for x in [26, 51, 52, 27 * 26] {
+            let p = Point::new(x, 0);
+            assert_eq!(p.to_string().parse(), Ok(p));
+            assert_eq!(Point::parse_on(&p.to_string(), size), Err(ParsePointError::OutOfBoard));
+        }
+    }
+
+    #[test]
+    fn action() {
+        assert_eq!("pass".parse(), Ok(Action::Pass));
+        assert_eq!("PA".parse(), Ok(Action::Pass));
+        assert_eq!("c4".parse(), Ok(Action::Put(Point::new(2, 3))));
+        assert_eq!(Action::parse_on("z1", 8), Err(ParsePointError::OutOfBoard));
+        assert_eq!(Action::Pass.to_string(), "pass");
+    }
 }
@@ -0,0 +1,298 @@
+// Position strings: every square row by row, then the side to move, e.g.
+// "---------------------------OX------XO--------------------------- X"
+
+use std::{fmt::Display, str::FromStr};
+
+use crate::{
+    bitboard::BitBoard,
+    board::{is_supported_size, ArrayBasedBoard, Board, ReversiBoard, DEFAULT_BOARD_SIZE},
+    error::ParsePositionError,
+    game::SimpleReversiGame,
+    stone::Stone,
+    symmetry::{self, Symmetry},
+};
+
+// Black at c1 makes White pass.
+#[cfg(test)]
+pub(crate) const PASS_POSITION: &str =
+    "XO------ -------- -------- -------- -------- -------- -------- ------OX X";
+
+/// A square board of even size, at least 4x4, and the side to move. Only
+/// positions of that shape can be built, so they always load into a board.
+/// Boards can't be sent between threads, positions can.
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Position {
+    cells: Board,
+    turn: Stone,
+}
+
+impl Position {
+    pub fn new(cells: Board, turn: Stone) -> Result<Self, ParsePositionError> {
+        let size = cells.len();
+        if !is_supported_size(size) || cells.iter().any(|row| row.len() != size) {
+            let squares = cells.iter().map(Vec::len).sum();
+            return Err(ParsePositionError::InvalidLength(squares));
+        }
+
+        Ok(Self { cells, turn })
+    }
+
+    pub fn from_board(board: &dyn ReversiBoard, turn: Stone) -> Self {
+        let size = board.size();
+        let cells = (0..size)
+            .map(|y| (0..size).map(|x| board.get_at(x, y)).collect())
+            .collect();
+
+        Self { cells, turn }
+    }
+
+    #[inline]
+    pub fn size(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// The squares, indexed as `cells[y][x]`.
+    #[inline]
+    pub fn cells(&self) -> &Board {
+        &self.cells
+    }
+
+    #[inline]
+    pub fn turn(&self) -> Stone {
+        self.turn
+    }
+
+    /// Overwrites every square of `board`, which must have the same size.
+    pub fn load_into(&self, board: &mut dyn ReversiBoard) -> Result<(), ParsePositionError> {
+        if board.size() != self.size() {
+            return Err(ParsePositionError::SizeMismatch);
+        }
+
+        for (y, row) in self.cells.iter().enumerate() {
+            for (x, &cell) in row.iter().enumerate() {
+                board
+                    .set_at(x, y, cell)
+                    .map_err(|_| ParsePositionError::SizeMismatch)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn to_board(&self) -> Box<dyn ReversiBoard> {
+        let mut board = ArrayBasedBoard::with_size(self.size());
+        self.load_into(&mut board).unwrap();
+
+        Box::new(board)
+    }
+
+    /// A `BitBoard` for 8x8 positions, and an `ArrayBasedBoard` otherwise.
+    pub fn to_fast_board(&self) -> Box<dyn ReversiBoard> {
+        if self.size() != DEFAULT_BOARD_SIZE {
+            return self.to_board();
+        }
+
+        let mut board = BitBoard::new();
+        self.load_into(&mut board).unwrap();
+
+        Box::new(board)
+    }
+
+    pub fn transformed(&self, symmetry: Symmetry) -> Self {
+        Self {
+            cells: symmetry.apply_cells(&self.cells),
+            turn: self.turn,
+        }
+    }
+
+    /// The canonical form of the position, see `symmetry::canonical`.
+    pub fn canonical(&self) -> (Self, Symmetry) {
+        let (cells, symmetry) = symmetry::canonical(&self.cells);
+
+        (
+            Self {
+                cells,
+                turn: self.turn,
+            },
+            symmetry,
+        )
+    }
+
+    pub fn to_game(&self) -> SimpleReversiGame {
+        SimpleReversiGame::with_position(self.to_board(), self.turn)
+    }
+
+    pub(crate) fn parse_square(c: char) -> Result<Option<Stone>, ParsePositionError> {
+        match c {
+            'X' | 'x' | '*' | 'B' | 'b' => Ok(Some(Stone::Black)),
+            'O' | 'o' | 'W' | 'w' => Ok(Some(Stone::White)),
+            '-' | '.' => Ok(None),
+            _ => Err(ParsePositionError::InvalidSquare(c)),
+        }
+    }
+
+    pub(crate) fn parse_cells(squares: &[char]) -> Result<Board, ParsePositionError> {
+        let size = (1..=squares.len())
+            .find(|n| n * n >= squares.len())
+            .unwrap_or(0);
+        if size * size != squares.len() {
+            return Err(ParsePositionError::InvalidLength(squares.len()));
+        }
+
+        let mut cells = vec![vec![None; size]; size];
+        for (i, &c) in squares.iter().enumerate() {
+            cells[i / size][i % size] = Self::parse_square(c)?;
+        }
+
+        Ok(cells)
+    }
+}
+
+impl FromStr for Position {
+    type Err = ParsePositionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let squares: Vec<char> = s.chars().filter(|c| !c.is_whitespace()).collect();
+        let Some((&turn, squares)) = squares.split_last() else {
+            return Err(ParsePositionError::InvalidLength(0));
+        };
+
+        let cells = Self::parse_cells(squares)?;
+        let turn = match Self::parse_square(turn) {
+            Ok(Some(turn)) => turn,
+            _ => return Err(ParsePositionError::InvalidTurn(turn)),
+        };
+
+        Self::new(cells, turn)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Position {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        struct Fields {
+            cells: Board,
+            turn: Stone,
+        }
+
+        let fields = Fields::deserialize(deserializer)?;
+        Self::new(fields.cells, fields.turn)
+            .map_err(|error| serde::de::Error::custom(format!("{:?}", error)))
+    }
+}
+
+impl Display for Position {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for cell in self.cells.iter().flatten() {
+            write!(f, "{}", square_char(*cell))?;
+        }
+
+        write!(f, " {}", square_char(Some(self.turn)))
+    }
+}
+
+fn square_char(stone: Option<Stone>) -> char {
+    match stone {
+        Some(Stone::Black) => 'X',
+        Some(Stone::White) => 'O',
+        None => '-',
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const START: &str = "---------------------------OX------XO--------------------------- X";
+
+    #[test]
+    fn parse() {
+        let position: Position = START.parse().unwrap();
+        let game = SimpleReversiGame::new();
+
+        assert_eq!(position.size(), 8);
+        assert_eq!(position.turn(), Stone::Black);
+        assert_eq!(position.cells(), game.board().board());
+        assert_eq!(position.to_string(), START);
+        assert_eq!(game.position(), position);
+    }
+
+    #[test]
+    fn parse_other_sizes() {
+        let position: Position = "---- -OX- -XO- ---- O".parse().unwrap();
+        assert_eq!(position.size(), 4);
+        assert_eq!(position.turn(), Stone::White);
+        assert_eq!(position.to_board().get_can_put_stones(Stone::White).len(), 4);
+
+        let mut board = BitBoard::new();
+        assert_eq!(
+            position.load_into(&mut board),
+            Err(ParsePositionError::SizeMismatch)
+        );
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            "".parse::<Position>(),
+            Err(ParsePositionError::InvalidLength(0))
+        );
+        assert_eq!(
+            "--------- X".parse::<Position>(),
+            Err(ParsePositionError::InvalidLength(9))
+        );
+        assert_eq!(
+            "---- -OX- -XO- ---? X".parse::<Position>(),
+            Err(ParsePositionError::InvalidSquare('?'))
+        );
+        assert_eq!(
+            "---- -OX- -XO- ---- -".parse::<Position>(),
+            Err(ParsePositionError::InvalidTurn('-'))
+        );
+    }
+
+    #[test]
+    fn shape() {
+        let ragged = vec![vec![None; 4], vec![None; 4], vec![None; 3], vec![None; 4]];
+        assert_eq!(
+            Position::new(ragged, Stone::Black),
+            Err(ParsePositionError::InvalidLength(15))
+        );
+        assert_eq!(
+            Position::new(vec![vec![None; 5]; 5], Stone::Black),
+            Err(ParsePositionError::InvalidLength(25))
+        );
+        assert_eq!(
+            Position::new(vec![vec![None; 2]; 2], Stone::Black),
+            Err(ParsePositionError::InvalidLength(4))
+        );
+        assert_eq!(
+            Position::new(vec![vec![None; 28]; 28], Stone::Black),
+            Err(ParsePositionError::InvalidLength(784))
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn deserialize_checks_shape() {
+        let position: Position = START.parse().unwrap();
+        let json = serde_json::to_string(&position).unwrap();
+        assert_eq!(serde_json::from_str::<Position>(&json).unwrap(), position);
+
+        let json = r#"{"cells":[[null,null],[null,null]],"turn":"Black"}"#;
+        assert!(serde_json::from_str::<Position>(json).is_err());
+    }
+
+    #[test]
+    fn load_into_bitboard() {
+        let position: Position = PASS_POSITION.parse().unwrap();
+        let mut board = BitBoard::new();
+        position.load_into(&mut board).unwrap();
+
+        assert_eq!(board.count(Stone::Black), 2);
+        assert!(board.check_can_put(2, 0, Stone::Black));
+        assert_eq!(Position::from_board(&board, Stone::Black), position);
+    }
+}
@@ -0,0 +1,4 @@
+// Game records in the formats used by other Othello programs.
+
+pub mod ggf;
+pub mod wthor;
@@ -0,0 +1,576 @@
+// Generic Game Format, as used by GGS and NBoard:
+// (;GM[Othello]PB[...]PW[...]TY[8]BO[8 ... *]B[f5//1.02]W[d6/-2.00/0.5];)
+
+use std::{
+    fmt::{Display, Write},
+    str::FromStr,
+};
+
+use crate::{
+    board::{is_supported_size, ArrayBasedBoard, DEFAULT_BOARD_SIZE},
+    computer::Computer,
+    error::{RecordError, ReplayError, ReplayErrorKind},
+    game::{Move, SimpleReversiGame},
+    point::Action,
+    position::Position,
+    stone::Stone,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GgfMove {
+    pub player: Stone,
+    pub action: Action,
+    pub eval: Option<f64>,
+    pub time: Option<String>,
+}
+
+impl GgfMove {
+    pub fn new(player: Stone, action: Action) -> Self {
+        Self {
+            player,
+            action,
+            eval: None,
+            time: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GgfGame {
+    pub place: Option<String>,
+    pub date: Option<String>,
+    pub black: Option<String>,
+    pub white: Option<String>,
+    pub black_rating: Option<f64>,
+    pub white_rating: Option<f64>,
+    /// `Computer::seed` of the players, to replay games between computers.
+    pub black_seed: Option<u64>,
+    pub white_seed: Option<u64>,
+    pub time_control: Option<String>,
+    pub board_type: Option<String>,
+    pub result: Option<String>,
+    pub board: Option<Position>,
+    pub moves: Vec<GgfMove>,
+    /// Properties this module does not interpret, kept in their original order.
+    pub other: Vec<(String, String)>,
+}
+
+impl GgfGame {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records the moves of `game`, starting from its initial position.
+    pub fn from_game(game: &SimpleReversiGame) -> Self {
+        let (board, turn) = game.start_position();
+
+        let moves = game
+            .history()
+            .iter()
+            .map(|m| match m {
+                Move::Put { player, point, .. } => GgfMove::new(*player, Action::Put(*point)),
+                Move::Pass(player) => GgfMove::new(*player, Action::Pass),
+            })
+            .collect();
+
+        Self {
+            board_type: Some(board.size().to_string()),
+            board: Some(Position::from_board(board.as_ref(), turn)),
+            moves,
+            ..Self::default()
+        }
+    }
+
+    /// Like `from_game`, also recording the seeds of the computers that
+    /// played it.
+    pub fn from_game_with_players(
+        game: &SimpleReversiGame,
+        black: &dyn Computer,
+        white: &dyn Computer,
+    ) -> Self {
+        Self {
+            black_seed: black.seed(),
+            white_seed: white.seed(),
+            ..Self::from_game(game)
+        }
+    }
+
+    pub fn to_game(&self) -> Result<SimpleReversiGame, RecordError> {
+        let mut game = match &self.board {
+            Some(position) => position.to_game(),
+            None => SimpleReversiGame::with_board(Box::new(ArrayBasedBoard::with_size(
+                self.board_size()?,
+            ))),
+        };
+
+        // Passes forced by a move may be written out or not.
+        let mut pass_pending = false;
+
+        for (i, m) in self.moves.iter().enumerate() {
+            let ply = i + 1;
+            let expected = match pass_pending {
+                true => game.turn().opposite(),
+                false => game.turn(),
+            };
+            if m.player != expected && !game.is_game_over() {
+                return Err(ReplayError::new(ply, ReplayErrorKind::WrongPlayer(m.player)).into());
+            }
+
+            if m.action == Action::Pass && pass_pending {
+                pass_pending = false;
+                continue;
+            }
+
+            game.replay([m.action])
+                .map_err(|e| ReplayError::new(ply, e.kind))?;
+            pass_pending = m.action != Action::Pass
+                && matches!(game.history().last(), Some(Move::Pass(_)));
+        }
+
+        Ok(game)
+    }
+
+    // The size of the board, given by the board or else by the type, such as
+    // "8" or "10r".
+    fn board_size(&self) -> Result<usize, RecordError> {
+        if let Some(board) = &self.board {
+            return Ok(board.size());
+        }
+        let Some(board_type) = &self.board_type else {
+            return Ok(DEFAULT_BOARD_SIZE);
+        };
+
+        let digits: String = board_type
+            .chars()
+            .skip_while(|c| !c.is_ascii_digit())
+            .take_while(char::is_ascii_digit)
+            .collect();
+        match digits.parse() {
+            Ok(size) if is_supported_size(size) => Ok(size),
+            size => Err(RecordError::UnsupportedBoardSize(size.unwrap_or(0))),
+        }
+    }
+
+    /// Parses every game in `s`, such as the contents of a GGF archive.
+    pub fn parse_all(s: &str) -> Result<Vec<GgfGame>, RecordError> {
+        let mut parser = Parser::new(s);
+        let mut result: Vec<GgfGame> = Vec::new();
+
+        while let Some(game) = parser.next_game()? {
+            result.push(game);
+        }
+
+        Ok(result)
+    }
+
+    fn set_property(&mut self, key: &str, value: String) -> Result<(), RecordError> {
+        let invalid = || RecordError::InvalidProperty(key.to_string());
+
+        match key {
+            "GM" => {}
+            "PC" => self.place = Some(value),
+            "DT" => self.date = Some(value),
+            "PB" => self.black = Some(value),
+            "PW" => self.white = Some(value),
+            "RB" => self.black_rating = Some(value.trim().parse().map_err(|_| invalid())?),
+            "RW" => self.white_rating = Some(value.trim().parse().map_err(|_| invalid())?),
+            "SB" => self.black_seed = Some(value.trim().parse().map_err(|_| invalid())?),
+            "SW" => self.white_seed = Some(value.trim().parse().map_err(|_| invalid())?),
+            "TI" => self.time_control = Some(value),
+            "TY" => self.board_type = Some(value),
+            "RE" => self.result = Some(value),
+            "BO" => self.board = Some(parse_board(&value).ok_or_else(invalid)?),
+            "B" | "W" => {
+                let player = if key == "B" { Stone::Black } else { Stone::White };
+                let size = self.board_size()?;
+                self.moves
+                    .push(parse_move(player, &value, size).ok_or_else(invalid)?);
+            }
+            _ => self.other.push((key.to_string(), value)),
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for GgfGame {
+    type Err = RecordError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Parser::new(s).next_game()?.ok_or(RecordError::NoGame)
+    }
+}
+
+impl Display for GgfGame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "(;GM[Othello]")?;
+
+        let texts = [
+            ("PC", &self.place),
+            ("DT", &self.date),
+            ("PB", &self.black),
+            ("PW", &self.white),
+        ];
+        for (key, value) in texts {
+            if let Some(value) = value {
+                write_property(f, key, value)?;
+            }
+        }
+
+        let ratings = [("RB", self.black_rating), ("RW", self.white_rating)];
+        for (key, value) in ratings {
+            if let Some(value) = value {
+                write_property(f, key, &value.to_string())?;
+            }
+        }
+
+        let seeds = [("SB", self.black_seed), ("SW", self.white_seed)];
+        for (key, value) in seeds {
+            if let Some(value) = value {
+                write_property(f, key, &value.to_string())?;
+            }
+        }
+
+        let texts = [
+            ("TI", &self.time_control),
+            ("TY", &self.board_type),
+            ("RE", &self.result),
+        ];
+        for (key, value) in texts {
+            if let Some(value) = value {
+                write_property(f, key, value)?;
+            }
+        }
+
+        for (key, value) in &self.other {
+            write_property(f, key, value)?;
+        }
+
+        if let Some(board) = &self.board {
+            write_property(f, "BO", &format_board(board))?;
+        }
+
+        for m in &self.moves {
+            let key = match m.player {
+                Stone::Black => "B",
+                Stone::White => "W",
+            };
+            write_property(f, key, &format_move(m))?;
+        }
+
+        write!(f, ";)")
+    }
+}
+
+fn write_property(f: &mut std::fmt::Formatter<'_>, key: &str, value: &str) -> std::fmt::Result {
+    write!(f, "{}[", key)?;
+    for c in value.chars() {
+        if c == ']' || c == '\\' {
+            f.write_char('\\')?;
+        }
+        f.write_char(c)?;
+    }
+    write!(f, "]")
+}
+
+fn parse_board(value: &str) -> Option<Position> {
+    let mut tokens = value.split_whitespace();
+    let size: usize = tokens
+        .next()?
+        .trim_end_matches(|c: char| !c.is_ascii_digit())
+        .parse()
+        .ok()?;
+
+    let squares: Vec<char> = tokens.flat_map(str::chars).collect();
+    let (&turn, squares) = squares.split_last()?;
+    if squares.len() != size * size {
+        return None;
+    }
+
+    let cells = Position::parse_cells(squares).ok()?;
+    let turn = Position::parse_square(turn).ok()??;
+
+    Position::new(cells, turn).ok()
+}
+
+fn format_board(board: &Position) -> String {
+    let mut result = board.size().to_string();
+
+    for row in board.cells() {
+        result.push(' ');
+        for cell in row {
+            result.push(stone_char(*cell));
+        }
+    }
+
+    result.push(' ');
+    result.push(stone_char(Some(board.turn())));
+
+    result
+}
+
+fn stone_char(stone: Option<Stone>) -> char {
+    match stone {
+        Some(Stone::Black) => '*',
+        Some(Stone::White) => 'O',
+        None => '-',
+    }
+}
+
+fn parse_move(player: Stone, value: &str, size: usize) -> Option<GgfMove> {
+    let mut parts = value.split('/');
+
+    let action = Action::parse_on(parts.next()?, size).ok()?;
+    let eval = match parts.next().map(str::trim) {
+        None | Some("") => None,
+        Some(eval) => Some(eval.parse().ok()?),
+    };
+    let time = match parts.next().map(str::trim) {
+        None | Some("") => None,
+        Some(time) => Some(time.to_string()),
+    };
+
+    Some(GgfMove {
+        player,
+        action,
+        eval,
+        time,
+    })
+}
+
+fn format_move(m: &GgfMove) -> String {
+    let action = match m.action {
+        Action::Put(point) => point.to_string(),
+        Action::Pass => "pa".to_string(),
+    };
+
+    match (&m.eval, &m.time) {
+        (None, None) => action,
+        (Some(eval), None) => format!("{}/{}", action, eval),
+        (None, Some(time)) => format!("{}//{}", action, time),
+        (Some(eval), Some(time)) => format!("{}/{}/{}", action, eval, time),
+    }
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(input: &'a str) -> Self {
+        Self { input, position: 0 }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.input[self.position..]
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.position += rest.len() - rest.trim_start().len();
+    }
+
+    fn next_game(&mut self) -> Result<Option<GgfGame>, RecordError> {
+        let Some(start) = self.rest().find("(;") else {
+            return Ok(None);
+        };
+        self.position += start + 2;
+
+        let mut game = GgfGame::new();
+        loop {
+            self.skip_whitespace();
+
+            if self.rest().starts_with(";)") {
+                self.position += 2;
+                return Ok(Some(game));
+            }
+
+            let key_length = self
+                .rest()
+                .find(|c: char| !c.is_ascii_uppercase())
+                .unwrap_or(self.rest().len());
+            if key_length == 0 || !self.rest()[key_length..].starts_with('[') {
+                return Err(RecordError::Syntax(self.position));
+            }
+
+            let key = &self.rest()[..key_length];
+            self.position += key_length + 1;
+            let value = self.read_value()?;
+            game.set_property(key, value)?;
+        }
+    }
+
+    fn read_value(&mut self) -> Result<String, RecordError> {
+        let mut value = String::new();
+        let mut chars = self.rest().char_indices();
+
+        while let Some((i, c)) = chars.next() {
+            match c {
+                ']' => {
+                    self.position += i + 1;
+                    return Ok(value);
+                }
+                '\\' => {
+                    if let Some((_, escaped)) = chars.next() {
+                        value.push(escaped);
+                    }
+                }
+                _ => value.push(c),
+            }
+        }
+
+        Err(RecordError::Syntax(self.input.len()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        computer::RandomComputer,
+        point::Point,
+    };
+
+    use super::*;
+
+    const RECORD: &str = "(;GM[Othello]PC[NIOS]DT[2003.12.15_13:24:03.MET]PB[Saio1200]PW[Saio3000]RB[2197.28]RW[2199.28]TI[15:00//02:00]TY[8]RE[+0.000]BO[8 -------- -------- -------- ---O*--- ---*O--- -------- -------- -------- *]B[d3//0.01]W[c5/-1.23/0.02]B[f6]W[f5];)";
+
+    #[test]
+    fn parse() {
+        let record: GgfGame = RECORD.parse().unwrap();
+
+        assert_eq!(record.place.as_deref(), Some("NIOS"));
+        assert_eq!(record.black.as_deref(), Some("Saio1200"));
+        assert_eq!(record.white_rating, Some(2199.28));
+        assert_eq!(record.time_control.as_deref(), Some("15:00//02:00"));
+        assert_eq!(record.board.as_ref().unwrap().turn(), Stone::Black);
+        assert_eq!(record.moves.len(), 4);
+        assert_eq!(record.moves[0].action, Action::Put(Point::new(3, 2)));
+        assert_eq!(record.moves[0].time.as_deref(), Some("0.01"));
+        assert_eq!(record.moves[1].eval, Some(-1.23));
+
+        let game = record.to_game().unwrap();
+        assert_eq!(game.transcript(), "d3c5f6f5");
+        assert_eq!(game.turn(), Stone::Black);
+    }
+
+    #[test]
+    fn round_trip() {
+        let record: GgfGame = RECORD.parse().unwrap();
+        let written = record.to_string();
+
+        assert_eq!(written.parse::<GgfGame>().unwrap(), record);
+        assert!(written.contains("B[d3//0.01]W[c5/-1.23/0.02]B[f6]W[f5];)"));
+
+        let mut record = record;
+        record.white_rating = Some(2199.125);
+        record.moves[1].eval = Some(-1.2345);
+        assert_eq!(record.to_string().parse::<GgfGame>().unwrap(), record);
+    }
+
+    #[test]
+    fn board_size_from_type() {
+        let record: GgfGame = "(;GM[Othello]TY[10r]B[e4];)".parse().unwrap();
+        let game = record.to_game().unwrap();
+        assert_eq!(game.board().size(), 10);
+        assert_eq!(game.transcript(), "e4");
+
+        for size in [7, 28] {
+            let record: GgfGame = format!("(;GM[Othello]TY[{}];)", size).parse().unwrap();
+            assert_eq!(
+                record.to_game().err(),
+                Some(RecordError::UnsupportedBoardSize(size))
+            );
+        }
+    }
+
+    #[test]
+    fn from_game() {
+        let game = SimpleReversiGame::from_transcript("f5d6c3d3c4").unwrap();
+        let record = GgfGame::from_game(&game);
+
+        assert_eq!(record.to_game().unwrap().transcript(), "f5d6c3d3c4");
+        assert_eq!(
+            GgfGame::parse_all(&format!("{}\n{}", record, RECORD))
+                .unwrap()
+                .len(),
+            2
+        );
+
+        // The pass is written out.
+        let position: Position = crate::position::PASS_POSITION.parse().unwrap();
+        let mut game = position.to_game();
+        game.put_stone(2, 0).unwrap();
+        let record = GgfGame::from_game(&game);
+        assert_eq!(record.moves[1].action, Action::Pass);
+        assert_eq!(record.to_game().unwrap().history(), game.history());
+    }
+
+    #[test]
+    fn seeds() {
+        let play = |black: RandomComputer, white: RandomComputer| {
+            let mut game = SimpleReversiGame::new();
+            while !game.is_game_over() {
+                let p = match game.turn() {
+                    Stone::Black => black.decide(game.board()),
+                    Stone::White => white.decide(game.board()),
+                };
+                game.put_stone(p.x, p.y).unwrap();
+            }
+
+            GgfGame::from_game_with_players(&game, &black, &white)
+        };
+
+        let record = play(RandomComputer::new(Stone::Black), RandomComputer::new(Stone::White));
+        let written: GgfGame = record.to_string().parse().unwrap();
+        assert_eq!(written, record);
+
+        // The computers make the same moves again with the recorded seeds.
+        let replayed = play(
+            RandomComputer::with_seed(Stone::Black, written.black_seed.unwrap()),
+            RandomComputer::with_seed(Stone::White, written.white_seed.unwrap()),
+        );
+        assert_eq!(replayed.moves, record.moves);
+    }
+
+    #[test]
+    fn wrong_player() {
+        let record: GgfGame = "(;GM[Othello]TY[8]B[f5]B[d6];)".parse().unwrap();
+
+        assert_eq!(
+            record.to_game().err(),
+            Some(RecordError::Replay(ReplayError::new(
+                2,
+                ReplayErrorKind::WrongPlayer(Stone::Black)
+            )))
+        );
+
+        // Not a legal move for White either.
+        let record: GgfGame = "(;GM[Othello]TY[8]B[f5]B[e6];)".parse().unwrap();
+        assert_eq!(
+            record.to_game().err(),
+            Some(RecordError::Replay(ReplayError::new(
+                2,
+                ReplayErrorKind::WrongPlayer(Stone::Black)
+            )))
+        );
+
+        let record: GgfGame = "(;GM[Othello]TY[8]B[f5]B[pa];)".parse().unwrap();
+        assert_eq!(
+            record.to_game().err(),
+            Some(RecordError::Replay(ReplayError::new(
+                2,
+                ReplayErrorKind::WrongPlayer(Stone::Black)
+            )))
+        );
+    }
+
+    #[test]
+    fn syntax_error() {
+        assert_eq!(
+            "(;GM[Othello]PB[x".parse::<GgfGame>(),
+            Err(RecordError::Syntax(17))
+        );
+        assert_eq!("".parse::<GgfGame>(), Err(RecordError::NoGame));
+    }
+}
@@ -0,0 +1,270 @@
+// WTHOR databases of the French Othello Federation: games (.wtb), players
+// (WTHOR.JOU) and tournaments (WTHOR.TRN). Numbers are little-endian.
+
+use std::io::Read;
+
+use crate::{
+    board::DEFAULT_BOARD_SIZE,
+    error::{ParsePointError, RecordError, ReplayError, ReplayErrorKind},
+    game::SimpleReversiGame,
+    point::{Action, Point},
+};
+
+const HEADER_SIZE: usize = 16;
+const MOVES_PER_GAME: usize = 60;
+const GAME_SIZE: usize = 8 + MOVES_PER_GAME;
+const PLAYER_NAME_SIZE: usize = 20;
+const TOURNAMENT_NAME_SIZE: usize = 26;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WthorHeader {
+    pub century: u8,
+    pub year: u8,
+    pub month: u8,
+    pub day: u8,
+    /// Number of games in a .wtb file.
+    pub games: u32,
+    /// Number of names in a .JOU or .TRN file.
+    pub names: u16,
+    pub game_year: u16,
+    pub board_size: u8,
+    pub game_type: u8,
+    /// Depth at which `WthorGame::theoretical_score` was computed.
+    pub depth: u8,
+}
+
+impl WthorHeader {
+    fn from_bytes(bytes: &[u8; HEADER_SIZE]) -> Self {
+        Self {
+            century: bytes[0],
+            year: bytes[1],
+            month: bytes[2],
+            day: bytes[3],
+            games: u32::from_le_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]),
+            names: u16::from_le_bytes([bytes[8], bytes[9]]),
+            game_year: u16::from_le_bytes([bytes[10], bytes[11]]),
+            board_size: bytes[12],
+            game_type: bytes[13],
+            depth: bytes[14],
+        }
+    }
+
+    /// The board size of the games. Older files write 0 for 8x8.
+    pub fn board_size(&self) -> usize {
+        match self.board_size {
+            0 => DEFAULT_BOARD_SIZE,
+            size => size as usize,
+        }
+    }
+
+    pub fn read<R: Read>(reader: &mut R) -> Result<Self, RecordError> {
+        let mut bytes = [0; HEADER_SIZE];
+        reader.read_exact(&mut bytes)?;
+
+        Ok(Self::from_bytes(&bytes))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WthorGame {
+    pub tournament: u16,
+    pub black: u16,
+    pub white: u16,
+    /// Black's discs at the end of the game.
+    pub black_score: u8,
+    /// Black's discs with perfect play from `WthorHeader::depth` empties.
+    pub theoretical_score: u8,
+    pub moves: Vec<Point>,
+}
+
+impl WthorGame {
+    fn from_bytes(bytes: &[u8; GAME_SIZE]) -> Result<Self, RecordError> {
+        let mut moves: Vec<Point> = Vec::new();
+
+        for (i, &square) in bytes[8..].iter().enumerate() {
+            if square == 0 {
+                break;
+            }
+
+            let (x, y) = (square % 10, square / 10);
+            if !(1..=8).contains(&x) || !(1..=8).contains(&y) {
+                return Err(ReplayError::new(
+                    i + 1,
+                    ReplayErrorKind::Parse(ParsePointError::OutOfBoard),
+                )
+                .into());
+            }
+
+            moves.push(Point::new(x as usize - 1, y as usize - 1));
+        }
+
+        Ok(Self {
+            tournament: u16::from_le_bytes([bytes[0], bytes[1]]),
+            black: u16::from_le_bytes([bytes[2], bytes[3]]),
+            white: u16::from_le_bytes([bytes[4], bytes[5]]),
+            black_score: bytes[6],
+            theoretical_score: bytes[7],
+            moves,
+        })
+    }
+
+    /// Replays the game from the standard starting position. Passes are not
+    /// stored in WTHOR files, so they are inferred.
+    pub fn to_game(&self) -> Result<SimpleReversiGame, RecordError> {
+        let mut game = SimpleReversiGame::new();
+        game.replay(self.moves.iter().copied().map(Action::Put))?;
+
+        Ok(game)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WthorDatabase {
+    pub header: WthorHeader,
+    pub games: Vec<WthorGame>,
+}
+
+impl WthorDatabase {
+    /// Reads a .wtb game file.
+    pub fn read<R: Read>(reader: &mut R) -> Result<Self, RecordError> {
+        let header = WthorHeader::read(reader)?;
+        if header.board_size() != DEFAULT_BOARD_SIZE {
+            return Err(RecordError::UnsupportedBoardSize(header.board_size()));
+        }
+
+        // The count is only trusted as far as there are games to read.
+        let mut games: Vec<WthorGame> = Vec::new();
+        let mut bytes = [0; GAME_SIZE];
+        for _ in 0..header.games {
+            reader.read_exact(&mut bytes)?;
+            games.push(WthorGame::from_bytes(&bytes)?);
+        }
+
+        Ok(Self { header, games })
+    }
+}
+
+/// Reads the player names of a WTHOR.JOU file, indexed by player number.
+pub fn read_players<R: Read>(reader: &mut R) -> Result<Vec<String>, RecordError> {
+    read_names(reader, PLAYER_NAME_SIZE)
+}
+
+/// Reads the tournament names of a WTHOR.TRN file, indexed by tournament number.
+pub fn read_tournaments<R: Read>(reader: &mut R) -> Result<Vec<String>, RecordError> {
+    read_names(reader, TOURNAMENT_NAME_SIZE)
+}
+
+fn read_names<R: Read>(reader: &mut R, size: usize) -> Result<Vec<String>, RecordError> {
+    let header = WthorHeader::read(reader)?;
+
+    let mut names: Vec<String> = Vec::with_capacity(header.names as usize);
+    let mut bytes = vec![0; size];
+    for _ in 0..header.names {
+        reader.read_exact(&mut bytes)?;
+
+        // Names are null-terminated ISO-8859-1.
+        let name = bytes
+            .iter()
+            .take_while(|&&b| b != 0)
+            .map(|&b| b as char)
+            .collect::<String>();
+        names.push(name.trim_end().to_string());
+    }
+
+    Ok(names)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header(games: u32, names: u16) -> Vec<u8> {
+        let mut bytes = vec![20, 24, 1, 15];
+        bytes.extend(games.to_le_bytes());
+        bytes.extend(names.to_le_bytes());
+        bytes.extend(2023u16.to_le_bytes());
+        bytes.extend([8, 0, 22, 0]);
+        bytes
+    }
+
+    fn game(transcript: &str, black_score: u8) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend(3u16.to_le_bytes());
+        bytes.extend(1u16.to_le_bytes());
+        bytes.extend(0u16.to_le_bytes());
+        bytes.extend([black_score, black_score]);
+
+        let mut moves = [0; MOVES_PER_GAME];
+        let actions = crate::transcript::parse(transcript, 8).unwrap();
+        for (square, action) in moves.iter_mut().zip(actions) {
+            let Action::Put(Point { x, y }) = action else {
+                continue;
+            };
+            *square = (10 * (y + 1) + x + 1) as u8;
+        }
+        bytes.extend(moves);
+        bytes
+    }
+
+    #[test]
+    fn read_database() {
+        let mut bytes = header(2, 0);
+        bytes.extend(game("f5d6c3d3c4", 32));
+        bytes.extend(game("f5f6", 40));
+
+        let database = WthorDatabase::read(&mut bytes.as_slice()).unwrap();
+        assert_eq!(database.header.game_year, 2023);
+        assert_eq!(database.header.depth, 22);
+        assert_eq!(database.games.len(), 2);
+        assert_eq!(database.games[0].tournament, 3);
+        assert_eq!(database.games[0].black, 1);
+        assert_eq!(database.games[1].black_score, 40);
+        assert_eq!(database.games[1].moves, vec![Point::new(5, 4), Point::new(5, 5)]);
+
+        let game = database.games[0].to_game().unwrap();
+        assert_eq!(game.transcript(), "f5d6c3d3c4");
+    }
+
+    #[test]
+    fn read_invalid_database() {
+        let mut bytes = header(2, 0);
+        bytes.extend(game("f5d6", 32));
+        assert_eq!(
+            WthorDatabase::read(&mut bytes.as_slice()),
+            Err(RecordError::Io(std::io::ErrorKind::UnexpectedEof))
+        );
+
+        let mut bytes = header(u32::MAX, 0);
+        bytes.extend(game("f5d6", 32));
+        assert_eq!(
+            WthorDatabase::read(&mut bytes.as_slice()),
+            Err(RecordError::Io(std::io::ErrorKind::UnexpectedEof))
+        );
+
+        let mut bytes = header(1, 0);
+        bytes.extend(game("f5d6", 32));
+        bytes[HEADER_SIZE + 9] = 19;
+        assert_eq!(
+            WthorDatabase::read(&mut bytes.as_slice()),
+            Err(RecordError::Replay(ReplayError::new(
+                2,
+                ReplayErrorKind::Parse(ParsePointError::OutOfBoard)
+            )))
+        );
+    }
+
+    #[test]
+    fn read_names() {
+        let mut bytes = header(0, 2);
+        for name in ["Tamenori Hideshi", "Brian Rose"] {
+            let mut record = [0; PLAYER_NAME_SIZE];
+            record[..name.len()].copy_from_slice(name.as_bytes());
+            bytes.extend(record);
+        }
+
+        assert_eq!(
+            read_players(&mut bytes.as_slice()).unwrap(),
+            vec!["Tamenori Hideshi", "Brian Rose"]
+        );
+    }
+}
@@ -0,0 +1,247 @@
+// Self-play games, labelled for training evaluators. Datasets have a
+// position string and a score per line; lines starting with '#' are ignored.
+
+use std::io::{BufRead, BufReader, Read, Write};
+
+use rand::{seq::SliceRandom, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use crate::{
+    computer::Computer, error::DatasetError, evaluation::Evaluator, game::SimpleReversiGame,
+    position::Position, stone::Stone,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sample {
+    pub position: Position,
+    /// From the point of view of the player to move.
+    pub score: i32,
+}
+
+/// How positions are scored.
+pub enum Label {
+    /// The final disc differential of the game.
+    FinalScore,
+    /// The evaluation of the position, e.g. by an `AlphaBetaComputer`.
+    Evaluation(Box<dyn Evaluator>),
+}
+
+pub struct SelfPlay {
+    black: Box<dyn Computer>,
+    white: Box<dyn Computer>,
+    random_plies: usize,
+    label: Label,
+    seed: u64,
+    rng: ChaCha8Rng,
+}
+
+impl SelfPlay {
+    /// Creates a self-play with a random seed, see `seed`.
+    pub fn new(black: Box<dyn Computer>, white: Box<dyn Computer>) -> Self {
+        Self::with_seed(black, white, rand::random())
+    }
+
+    /// Plays the same games every time for the same seed, as long as the
+    /// computers are seeded the same too.
+    pub fn with_seed(black: Box<dyn Computer>, white: Box<dyn Computer>, seed: u64) -> Self {
+        Self {
+            black,
+            white,
+            random_plies: 0,
+            label: Label::FinalScore,
+            seed,
+            rng: ChaCha8Rng::seed_from_u64(seed),
+        }
+    }
+
+    /// The seed of the random opening moves.
+    #[inline]
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    #[inline]
+    pub fn random_plies(&self) -> usize {
+        self.random_plies
+    }
+
+    /// Plays uniformly random moves for the first `plies` moves of each game.
+    pub fn set_random_plies(&mut self, plies: usize) {
+        self.random_plies = plies;
+    }
+
+    pub fn set_label(&mut self, label: Label) {
+        self.label = label;
+    }
+
+    /// Plays one game and returns its positions, excluding the final one.
+    pub fn play_game(&mut self) -> Vec<Sample> {
+        let mut game = SimpleReversiGame::new();
+        let mut positions = Vec::new();
+
+        while !game.is_game_over() {
+            positions.push(game.position());
+
+            let p = if game.history().len() < self.random_plies {
+                *game.get_can_put_stones().choose(&mut self.rng).unwrap()
+            } else {
+                match game.turn() {
+                    Stone::Black => self.black.decide(game.board()),
+                    Stone::White => self.white.decide(game.board()),
+                }
+            };
+
+            game.put_stone(p.x, p.y)
+                .expect("the computer should play a legal move");
+        }
+
+        positions
+            .into_iter()
+            .map(|position| {
+                let score = match &self.label {
+                    Label::FinalScore => {
+                        game.board().count(position.turn()) as i32
+                            - game.board().count(position.turn().opposite()) as i32
+                    }
+                    Label::Evaluation(evaluator) => {
+                        evaluator.evaluate(position.to_board().as_ref(), position.turn())
+                    }
+                };
+
+                Sample { position, score }
+            })
+            .collect()
+    }
+
+    /// Plays `games` games and writes their positions to `writer`, after
+    /// comment lines with the seed and those of the computers, see
+    /// `Computer::seed`. Returns the number of positions written.
+    pub fn play<W: Write>(&mut self, games: usize, writer: &mut W) -> Result<usize, DatasetError> {
+        writeln!(writer, "# seed {}", self.seed)?;
+        for (name, computer) in [("black", &self.black), ("white", &self.white)] {
+            if let Some(seed) = computer.seed() {
+                writeln!(writer, "# {} seed {}", name, seed)?;
+            }
+        }
+        let mut written = 0;
+
+        for _ in 0..games {
+            let samples = self.play_game();
+            write_samples(&samples, writer)?;
+            written += samples.len();
+        }
+
+        Ok(written)
+    }
+}
+
+pub fn write_samples<W: Write>(samples: &[Sample], writer: &mut W) -> Result<(), DatasetError> {
+    for sample in samples {
+        writeln!(writer, "{} {}", sample.position, sample.score)?;
+    }
+
+    Ok(())
+}
+
+pub fn read_samples<R: Read>(reader: R) -> Result<Vec<Sample>, DatasetError> {
+    let mut samples = Vec::new();
+
+    for (i, line) in BufReader::new(reader).lines().enumerate() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let syntax = DatasetError::Syntax(i + 1);
+        let [squares, turn, score] = line.split_whitespace().collect::<Vec<_>>()[..] else {
+            return Err(syntax);
+        };
+
+        let position: Position = format!("{} {}", squares, turn)
+            .parse()
+            .map_err(|_| syntax)?;
+        let score: i32 = score.parse().map_err(|_| syntax)?;
+
+        samples.push(Sample { position, score });
+    }
+
+    Ok(samples)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        computer::{AlphaBetaComputer, RandomComputer, WeightedComputer},
+        evaluation::DiscParity,
+    };
+
+    use super::*;
+
+    fn self_play(seed: u64) -> SelfPlay {
+        let mut self_play = SelfPlay::with_seed(
+            Box::new(WeightedComputer::new(Stone::Black)),
+            Box::new(WeightedComputer::new(Stone::White)),
+            seed,
+        );
+        self_play.set_random_plies(6);
+
+        self_play
+    }
+
+    #[test]
+    fn final_scores() {
+        let samples = self_play(22).play_game();
+        assert_eq!(samples[0].position, SimpleReversiGame::new().position());
+
+        let last = samples.last().unwrap();
+        for sample in &samples {
+            let same_turn = sample.position.turn() == last.position.turn();
+            assert_eq!(sample.score, if same_turn { last.score } else { -last.score });
+        }
+
+        // Random openings make different games.
+        let first: Vec<Sample> = self_play(22).play_game();
+        let other: Vec<Sample> = self_play(23).play_game();
+        assert_eq!(first, samples);
+        assert_ne!(first, other);
+    }
+
+    #[test]
+    fn evaluation_labels() {
+        let mut self_play = self_play(22);
+        let search = AlphaBetaComputer::with_evaluator(Stone::Black, 1, DiscParity);
+        self_play.set_label(Label::Evaluation(Box::new(search)));
+
+        // Every opening move flips one disc.
+        let samples = self_play.play_game();
+        assert_eq!(samples[0].score, 3);
+    }
+
+    #[test]
+    fn read_write() {
+        let mut bytes = Vec::new();
+        let written = self_play(22).play(2, &mut bytes).unwrap();
+
+        let samples = read_samples(bytes.as_slice()).unwrap();
+        assert_eq!(samples.len(), written);
+        assert!(bytes.starts_with(b"# seed 22\n"));
+        assert!(written > 100);
+
+        let mut self_play = SelfPlay::with_seed(
+            Box::new(RandomComputer::with_seed(Stone::Black, 1)),
+            Box::new(RandomComputer::with_seed(Stone::White, 2)),
+            3,
+        );
+        let mut bytes = Vec::new();
+        self_play.play(1, &mut bytes).unwrap();
+        assert!(bytes.starts_with(b"# seed 3\n# black seed 1\n# white seed 2\n"));
+
+        let text = "# start\n\n---------------------------OX------XO--------------------------- X 4\n";
+        let samples = read_samples(text.as_bytes()).unwrap();
+        assert_eq!(samples[0].score, 4);
+
+        let text = "---------------------------OX------XO--------------------------- X\n";
+        assert_eq!(read_samples(text.as_bytes()), Err(DatasetError::Syntax(1)));
+    }
+}
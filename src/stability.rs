@@ -0,0 +1,217 @@
+// Stable discs, which can never be flipped again.
+
+use crate::{bitboard::LINES, board::ReversiBoard, point::Point, stone::Stone};
+
+const AXES: [(isize, isize); 4] = [(1, 0), (0, 1), (1, 1), (1, -1)];
+
+/// Marks the stable discs of both colors, indexed as `grid[y][x]`.
+pub fn stable_grid(board: &dyn ReversiBoard) -> Vec<Vec<bool>> {
+    let size = board.size();
+    let full = full_lines(board);
+    let mut stable = vec![vec![false; size]; size];
+
+    // Stability only spreads from stable discs, so repeat until nothing changes.
+    let mut changed = true;
+    while changed {
+        changed = false;
+
+        for y in 0..size {
+            for x in 0..size {
+                if stable[y][x] {
+                    continue;
+                }
+                let Some(stone) = board.get_at(x, y) else {
+                    continue;
+                };
+
+                let anchored = |dx: isize, dy: isize| match step(board, x, y, dx, dy) {
+                    None => true,
+                    Some(p) => stable[p.y][p.x] && board.get_at(p.x, p.y) == Some(stone),
+                };
+
+                let is_stable = AXES.iter().enumerate().all(|(axis, &(dx, dy))| {
+                    full[axis][y][x] || anchored(dx, dy) || anchored(-dx, -dy)
+                });
+
+                if is_stable {
+                    stable[y][x] = true;
+                    changed = true;
+                }
+            }
+        }
+    }
+
+    stable
+}
+
+/// The stable discs of both colors.
+pub fn stable_discs(board: &dyn ReversiBoard) -> Vec<Point> {
+    let stable = stable_grid(board);
+
+    (0..board.size())
+        .flat_map(|y| (0..board.size()).map(move |x| Point::new(x, y)))
+        .filter(|p| stable[p.y][p.x])
+        .collect()
+}
+
+pub fn count_stable(board: &dyn ReversiBoard, player: Stone) -> usize {
+    stable_discs(board)
+        .into_iter()
+        .filter(|p| board.get_at(p.x, p.y) == Some(player))
+        .count()
+}
+
+/// `stable_grid` on an 8x8 board as `BitBoard` masks: the stable discs among
+/// `discs`, with `occupied` the squares of both colors.
+pub fn stable_mask(discs: u64, occupied: u64) -> u64 {
+    let lines = LINES.map(|(shift, left_mask, right_mask)| {
+        let left = move |b: u64| (b << shift) & left_mask;
+        let right = move |b: u64| (b >> shift) & right_mask;
+
+        // The squares on a line along this axis with an empty square.
+        let mut open = !occupied;
+        for _ in 0..7 {
+            open |= left(open) | right(open);
+        }
+
+        // A step to the right lands on a stable disc or off the board
+        // exactly for the squares a step to the left of them.
+        (left, right, !open, !left(!0), !right(!0))
+    });
+
+    let mut stable = 0;
+    loop {
+        let mut next = discs;
+        for (left, right, full, right_edge, left_edge) in lines {
+            next &= full | left(stable) | right_edge | right(stable) | left_edge;
+        }
+
+        if next == stable {
+            return stable;
+        }
+        stable = next;
+    }
+}
+
+// The square next to (x, y) in the direction, None off the board.
+fn step(board: &dyn ReversiBoard, x: usize, y: usize, dx: isize, dy: isize) -> Option<Point> {
+    let (x, y) = (x.checked_add_signed(dx)?, y.checked_add_signed(dy)?);
+    board.in_range(x, y).then(|| Point::new(x, y))
+}
+
+// For each axis, whether the line through each square along it has no empty
+// square.
+fn full_lines(board: &dyn ReversiBoard) -> [Vec<Vec<bool>>; 4] {
+    let size = board.size();
+
+    AXES.map(|(dx, dy)| {
+        let mut full = vec![vec![false; size]; size];
+
+        for (y, row) in full.iter_mut().enumerate() {
+            for (x, cell) in row.iter_mut().enumerate() {
+                if board.get_at(x, y).is_none() {
+                    continue;
+                }
+
+                let mut line_full = true;
+                for (dx, dy) in [(dx, dy), (-dx, -dy)] {
+                    let mut p = Point::new(x, y);
+                    while let Some(next) = step(board, p.x, p.y, dx, dy) {
+                        if board.get_at(next.x, next.y).is_none() {
+                            line_full = false;
+                            break;
+                        }
+                        p = next;
+                    }
+                }
+
+                *cell = line_full;
+            }
+        }
+
+        full
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+
+    use crate::{bitboard::BitBoard, game::SimpleReversiGame, position::Position};
+
+    use super::*;
+
+    fn stable(position: &str) -> Vec<Point> {
+        let position: Position = position.parse().unwrap();
+        stable_discs(position.to_board().as_ref())
+    }
+
+    #[test]
+    fn no_stable_discs_at_start() {
+        let game = SimpleReversiGame::new();
+        assert!(stable_discs(game.board()).is_empty());
+    }
+
+    #[test]
+    fn edge_anchored() {
+        let discs = stable(
+            "XXO----X XO------ -------- -------- -------- -------- -------- O------- X",
+        );
+
+        assert_eq!(
+            discs,
+            vec![
+                Point::new(0, 0),
+                Point::new(1, 0),
+                Point::new(7, 0),
+                Point::new(0, 1),
+                Point::new(0, 7),
+            ]
+        );
+    }
+
+    #[test]
+    fn full_lines() {
+        // Every line through the white disc on d5 is full, but the black
+        // discs around it can still be flipped.
+        let discs = stable(
+            "---X---X X--X--X- -X-XXX-- --XXX--- XXXOXXXX --XXX--- -X-X-X-- X--X--X- X",
+        );
+        assert_eq!(discs, vec![Point::new(7, 0), Point::new(3, 4), Point::new(0, 7)]);
+
+        let mut board = SimpleReversiGame::new().position().to_board();
+        for y in 0..8 {
+            for x in 0..8 {
+                if board.get_at(x, y).is_none() {
+                    board.set_at(x, y, Some(Stone::White)).unwrap();
+                }
+            }
+        }
+
+        assert_eq!(count_stable(board.as_ref(), Stone::White), 62);
+        assert_eq!(count_stable(board.as_ref(), Stone::Black), 2);
+    }
+
+    #[test]
+    fn masks() {
+        let mut rng = StdRng::seed_from_u64(20);
+
+        for _ in 0..20 {
+            let mut game = SimpleReversiGame::new();
+            while let Some(p) = game.get_can_put_stones().choose(&mut rng).copied() {
+                game.put_stone(p.x, p.y).unwrap();
+
+                let mut board = BitBoard::new();
+                game.position().load_into(&mut board).unwrap();
+                let occupied = board.black() | board.white();
+                for player in [Stone::Black, Stone::White] {
+                    let expected = stable_discs(&board)
+                        .into_iter()
+                        .filter(|p| board.get_at(p.x, p.y) == Some(player))
+                        .fold(0, |mask, p| mask | 1 << (p.y * 8 + p.x));
+                    assert_eq!(stable_mask(board.mask(player), occupied), expected);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,182 @@
+// The 8 symmetries of the square board: rotations, mirrors and transposes.
+
+use crate::{
+    board::{Board, ReversiBoard},
+    point::Point,
+    stone::Stone,
+};
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Symmetry {
+    Identity,
+    /// A quarter turn clockwise: a1 goes to h1.
+    Rotate90,
+    Rotate180,
+    /// A quarter turn counterclockwise: a1 goes to a8.
+    Rotate270,
+    /// Swaps the a and h files.
+    FlipLeftRight,
+    /// Swaps the 1st and 8th ranks.
+    FlipUpDown,
+    /// Mirrors in the a1-h8 diagonal.
+    Transpose,
+    /// Mirrors in the h1-a8 diagonal.
+    AntiTranspose,
+}
+
+impl Symmetry {
+    pub const ALL: [Symmetry; 8] = [
+        Symmetry::Identity,
+        Symmetry::Rotate90,
+        Symmetry::Rotate180,
+        Symmetry::Rotate270,
+        Symmetry::FlipLeftRight,
+        Symmetry::FlipUpDown,
+        Symmetry::Transpose,
+        Symmetry::AntiTranspose,
+    ];
+
+    pub fn inverse(self) -> Self {
+        match self {
+            Symmetry::Rotate90 => Symmetry::Rotate270,
+            Symmetry::Rotate270 => Symmetry::Rotate90,
+            s => s,
+        }
+    }
+
+    /// Where the square `p` goes on a board of `size`.
+    pub fn apply(self, p: Point, size: usize) -> Point {
+        let n = size - 1;
+        let (x, y) = match self {
+            Symmetry::Identity => (p.x, p.y),
+            Symmetry::Rotate90 => (n - p.y, p.x),
+            Symmetry::Rotate180 => (n - p.x, n - p.y),
+            Symmetry::Rotate270 => (p.y, n - p.x),
+            Symmetry::FlipLeftRight => (n - p.x, p.y),
+            Symmetry::FlipUpDown => (p.x, n - p.y),
+            Symmetry::Transpose => (p.y, p.x),
+            Symmetry::AntiTranspose => (n - p.y, n - p.x),
+        };
+
+        Point::new(x, y)
+    }
+
+    pub fn apply_cells(self, cells: &Board) -> Board {
+        let size = cells.len();
+        let mut result = vec![vec![None; size]; size];
+
+        for (y, row) in cells.iter().enumerate() {
+            for (x, &cell) in row.iter().enumerate() {
+                let p = self.apply(Point::new(x, y), size);
+                result[p.y][p.x] = cell;
+            }
+        }
+
+        result
+    }
+
+    /// Moves every stone on `board` to where the symmetry takes it.
+    pub fn apply_board(self, board: &mut dyn ReversiBoard) {
+        let cells = self.apply_cells(&cells(board));
+
+        for (y, row) in cells.iter().enumerate() {
+            for (x, &cell) in row.iter().enumerate() {
+                board.set_at(x, y, cell).unwrap();
+            }
+        }
+    }
+}
+
+fn cells(board: &dyn ReversiBoard) -> Board {
+    let size = board.size();
+
+    (0..size)
+        .map(|y| (0..size).map(|x| board.get_at(x, y)).collect())
+        .collect()
+}
+
+// Orders squares like their characters in position strings: '-' < 'O' < 'X'.
+fn order(cells: &Board) -> Vec<u8> {
+    cells
+        .iter()
+        .flatten()
+        .map(|cell| match cell {
+            None => 0,
+            Some(Stone::White) => 1,
+            Some(Stone::Black) => 2,
+        })
+        .collect()
+}
+
+/// The canonical form of `cells`: the image under a symmetry that comes
+/// first as a position string, and the symmetry that gives it.
+pub fn canonical(cells: &Board) -> (Board, Symmetry) {
+    Symmetry::ALL
+        .iter()
+        .map(|&symmetry| (symmetry.apply_cells(cells), symmetry))
+        .min_by_key(|(cells, _)| order(cells))
+        .unwrap()
+}
+
+/// The canonical form of the stones on `board`, see `canonical`.
+pub fn canonical_board(board: &dyn ReversiBoard) -> (Board, Symmetry) {
+    canonical(&cells(board))
+}
+
+/// The symmetries that leave `cells` unchanged, always including `Identity`.
+pub fn invariants(cells: &Board) -> Vec<Symmetry> {
+    Symmetry::ALL
+        .into_iter()
+        .filter(|symmetry| symmetry.apply_cells(cells) == *cells)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{game::SimpleReversiGame, position::Position};
+
+    use super::*;
+
+    #[test]
+    fn inverse() {
+        let p = Point::new(1, 2);
+
+        for symmetry in Symmetry::ALL {
+            assert_eq!(symmetry.inverse().apply(symmetry.apply(p, 8), 8), p);
+        }
+        assert_eq!(Symmetry::Rotate90.apply(Point::new(0, 0), 8), Point::new(7, 0));
+        assert_eq!(Symmetry::Transpose.apply(p, 8), Point::new(2, 1));
+    }
+
+    #[test]
+    fn canonical_form() {
+        let game = SimpleReversiGame::from_transcript("f5d6").unwrap();
+        let (canonical, symmetry) = canonical_board(game.board());
+
+        // All the orientations of the opening have the same canonical form.
+        for s in Symmetry::ALL {
+            let mut board = dyn_clone::clone_box(game.board());
+            s.apply_board(board.as_mut());
+
+            let (other, _) = canonical_board(board.as_ref());
+            assert_eq!(other, canonical);
+        }
+
+        let mut board = dyn_clone::clone_box(game.board());
+        symmetry.apply_board(board.as_mut());
+        assert_eq!(board.board(), &canonical);
+
+        let start = Position::from_board(SimpleReversiGame::new().board(), Stone::Black);
+        assert_eq!(
+            invariants(start.cells()),
+            vec![
+                Symmetry::Identity,
+                Symmetry::Rotate180,
+                Symmetry::Transpose,
+                Symmetry::AntiTranspose
+            ]
+        );
+        assert_eq!(invariants(&canonical), vec![Symmetry::Identity]);
+    }
+}
@@ -0,0 +1,479 @@
+// Matches between computers, with Elo estimates and SPRT early stopping.
+
+use std::fmt::{self, Display};
+
+use crate::{computer::Computer, game::SimpleReversiGame, stone::Stone};
+
+/// Short lines of the three usual replies to f5, all close to even.
+pub const DEFAULT_OPENINGS: [&str; 8] = [
+    "f5d6c3d3c4",
+    "f5d6c5f4e3",
+    "f5d6c4d3c3",
+    "f5f6e6f4e3",
+    "f5f6e6f4g5",
+    "f5f4e3f6d3",
+    "f5f4e3f6e6",
+    "f5d6c3f4f6",
+];
+
+// The normal quantile for a two-sided 95% interval.
+const Z_95: f64 = 1.96;
+
+const MIN_VARIANCE: f64 = 1e-6;
+
+/// Wins, draws and losses of one side of a match.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MatchResult {
+    pub wins: usize,
+    pub draws: usize,
+    pub losses: usize,
+    /// The sum of the final disc differentials.
+    pub discs: i64,
+}
+
+impl MatchResult {
+    pub fn games(&self) -> usize {
+        self.wins + self.draws + self.losses
+    }
+
+    /// The same match seen from the other side.
+    pub fn reversed(&self) -> Self {
+        Self {
+            wins: self.losses,
+            draws: self.draws,
+            losses: self.wins,
+            discs: -self.discs,
+        }
+    }
+
+    /// Records a game that ended `discs` ahead, or behind if negative.
+    pub fn add_game(&mut self, discs: i32) {
+        match discs {
+            d if d > 0 => self.wins += 1,
+            d if d < 0 => self.losses += 1,
+            _ => self.draws += 1,
+        }
+        self.discs += discs as i64;
+    }
+
+    pub fn add(&mut self, other: &MatchResult) {
+        self.wins += other.wins;
+        self.draws += other.draws;
+        self.losses += other.losses;
+        self.discs += other.discs;
+    }
+
+    /// The fraction of points won, a draw being half a point.
+    pub fn score(&self) -> f64 {
+        (self.wins as f64 + self.draws as f64 / 2.0) / self.games() as f64
+    }
+
+    // The variance of the points of one game.
+    fn variance(&self) -> f64 {
+        let score = self.score();
+        let squares = self.wins as f64 * (1.0 - score).powi(2)
+            + self.draws as f64 * (0.5 - score).powi(2)
+            + self.losses as f64 * score.powi(2);
+
+        squares / self.games() as f64
+    }
+
+    /// The Elo difference, and the margin of its 95% confidence interval.
+    /// Both are infinite if every game was won or every game lost.
+    pub fn elo(&self) -> (f64, f64) {
+        let score = self.score();
+        if score == 0.0 || score == 1.0 {
+            return (elo_of_score(score), f64::INFINITY);
+        }
+
+        let error = Z_95 * (self.variance() / self.games() as f64).sqrt();
+        let low = elo_of_score((score - error).max(0.0));
+        let high = elo_of_score((score + error).min(1.0));
+
+        (elo_of_score(score), (high - low) / 2.0)
+    }
+}
+
+impl Display for MatchResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (elo, margin) = self.elo();
+        write!(
+            f,
+            "+{} ={} -{}, discs {:+}, Elo {:+.0} ± {:.0}",
+            self.wins, self.draws, self.losses, self.discs, elo, margin
+        )
+    }
+}
+
+fn elo_of_score(score: f64) -> f64 {
+    -400.0 * (1.0 / score - 1.0).log10()
+}
+
+fn score_of_elo(elo: f64) -> f64 {
+    1.0 / (1.0 + 10f64.powf(-elo / 400.0))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SprtDecision {
+    Continue,
+    /// The difference is more likely `elo0`.
+    AcceptH0,
+    /// The difference is more likely `elo1`.
+    AcceptH1,
+}
+
+/// Tests whether the Elo difference is `elo0` or `elo1`, with false
+/// positive and false negative rates `alpha` and `beta`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sprt {
+    pub elo0: f64,
+    pub elo1: f64,
+    pub alpha: f64,
+    pub beta: f64,
+}
+
+impl Sprt {
+    pub fn new(elo0: f64, elo1: f64) -> Self {
+        Self {
+            elo0,
+            elo1,
+            alpha: 0.05,
+            beta: 0.05,
+        }
+    }
+
+    /// The log-likelihood ratio of `elo1` over `elo0`, in the usual normal
+    /// approximation.
+    pub fn llr(&self, result: &MatchResult) -> f64 {
+        if result.games() == 0 {
+            return 0.0;
+        }
+        // All games with the same result would make the ratio infinite.
+        let variance = result.variance().max(MIN_VARIANCE);
+
+        let (s0, s1) = (score_of_elo(self.elo0), score_of_elo(self.elo1));
+        let games = result.games() as f64;
+
+        games * (s1 - s0) * (2.0 * result.score() - s0 - s1) / (2.0 * variance)
+    }
+
+    pub fn test(&self, result: &MatchResult) -> SprtDecision {
+        let llr = self.llr(result);
+
+        if llr >= ((1.0 - self.beta) / self.alpha).ln() {
+            SprtDecision::AcceptH1
+        } else if llr <= (self.beta / (1.0 - self.alpha)).ln() {
+            SprtDecision::AcceptH0
+        } else {
+            SprtDecision::Continue
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Format {
+    /// Everyone plays everyone.
+    #[default]
+    RoundRobin,
+    /// The first entrant plays everyone else.
+    Gauntlet,
+}
+
+struct Entrant {
+    name: String,
+    make: Box<dyn Fn(Stone) -> Box<dyn Computer>>,
+}
+
+/// The result of one pairing, from the point of view of `first`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pairing {
+    pub first: usize,
+    pub second: usize,
+    pub result: MatchResult,
+    /// Set if the match was stopped by the SPRT.
+    pub decision: Option<SprtDecision>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report {
+    pub names: Vec<String>,
+    pub pairings: Vec<Pairing>,
+}
+
+impl Report {
+    /// All the games of `entrant`, from its point of view.
+    pub fn total(&self, entrant: usize) -> MatchResult {
+        let mut total = MatchResult::default();
+
+        for pairing in &self.pairings {
+            if pairing.first == entrant {
+                total.add(&pairing.result);
+            } else if pairing.second == entrant {
+                total.add(&pairing.result.reversed());
+            }
+        }
+
+        total
+    }
+}
+
+impl Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for pairing in &self.pairings {
+            write!(
+                f,
+                "{} vs {}: {}",
+                self.names[pairing.first], self.names[pairing.second], pairing.result
+            )?;
+            match pairing.decision {
+                Some(SprtDecision::AcceptH0) => writeln!(f, " (SPRT: H0)")?,
+                Some(SprtDecision::AcceptH1) => writeln!(f, " (SPRT: H1)")?,
+                _ => writeln!(f)?,
+            }
+        }
+
+        for (i, name) in self.names.iter().enumerate() {
+            writeln!(f, "{}: {}", name, self.total(i))?;
+        }
+
+        Ok(())
+    }
+}
+
+pub struct Tournament {
+    entrants: Vec<Entrant>,
+    format: Format,
+    openings: Vec<String>,
+    games: usize,
+    sprt: Option<Sprt>,
+}
+
+impl Tournament {
+    pub fn new() -> Self {
+        Self {
+            entrants: Vec::new(),
+            format: Format::RoundRobin,
+            openings: DEFAULT_OPENINGS.iter().map(|s| s.to_string()).collect(),
+            games: 2 * DEFAULT_OPENINGS.len(),
+            sprt: None,
+        }
+    }
+
+    /// Registers a computer configuration. Computers play one color each, so
+    /// `make` builds one for the given color.
+    pub fn add<F>(&mut self, name: &str, make: F)
+    where
+        F: Fn(Stone) -> Box<dyn Computer> + 'static,
+    {
+        self.entrants.push(Entrant {
+            name: name.to_string(),
+            make: Box::new(make),
+        });
+    }
+
+    pub fn set_format(&mut self, format: Format) {
+        self.format = format;
+    }
+
+    /// Sets the openings games start from, as transcripts. Each is played
+    /// with both colors, in turn, until a pairing has played its games.
+    pub fn set_openings<I, S>(&mut self, openings: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.openings = openings.into_iter().map(Into::into).collect();
+    }
+
+    /// Sets the number of games of each pairing, rounded up to an even
+    /// number so that both sides play both colors.
+    pub fn set_games(&mut self, games: usize) {
+        self.games = games + games % 2;
+    }
+
+    /// Stops each pairing as soon as `sprt` reaches a decision.
+    pub fn set_sprt(&mut self, sprt: Sprt) {
+        self.sprt = Some(sprt);
+    }
+
+    pub fn run(&self) -> Report {
+        let n = self.entrants.len();
+        let pairs: Vec<(usize, usize)> = match self.format {
+            Format::RoundRobin => (0..n)
+                .flat_map(|i| (i + 1..n).map(move |j| (i, j)))
+                .collect(),
+            Format::Gauntlet => (1..n).map(|j| (0, j)).collect(),
+        };
+
+        Report {
+            names: self.entrants.iter().map(|e| e.name.clone()).collect(),
+            pairings: pairs
+                .into_iter()
+                .map(|(first, second)| self.play_match(first, second))
+                .collect(),
+        }
+    }
+
+    fn play_match(&self, first: usize, second: usize) -> Pairing {
+        let (first_entrant, second_entrant) = (&self.entrants[first], &self.entrants[second]);
+        let first_black = (first_entrant.make)(Stone::Black);
+        let first_white = (first_entrant.make)(Stone::White);
+        let second_black = (second_entrant.make)(Stone::Black);
+        let second_white = (second_entrant.make)(Stone::White);
+
+        let mut result = MatchResult::default();
+        let mut decision = None;
+
+        for opening in self.openings.iter().cycle().take(self.games / 2) {
+            let discs = play_game(opening, first_black.as_ref(), second_white.as_ref());
+            result.add_game(discs);
+            let discs = play_game(opening, second_black.as_ref(), first_white.as_ref());
+            result.add_game(-discs);
+
+            // Only decide after full pairs of games, or the color would bias
+            // the test.
+            if let Some(sprt) = &self.sprt {
+                let tested = sprt.test(&result);
+                if tested != SprtDecision::Continue {
+                    decision = Some(tested);
+                    break;
+                }
+            }
+        }
+
+        Pairing {
+            first,
+            second,
+            result,
+            decision,
+        }
+    }
+}
+
+impl Default for Tournament {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Plays a game from `opening` and returns Black's final disc differential.
+///
+/// # Panics
+/// Panics if `opening` is not a valid transcript.
+pub fn play_game(opening: &str, black: &dyn Computer, white: &dyn Computer) -> i32 {
+    let mut game = SimpleReversiGame::from_transcript(opening).expect("invalid opening");
+
+    while !game.is_game_over() {
+        let p = match game.turn() {
+            Stone::Black => black.decide(game.board()),
+            Stone::White => white.decide(game.board()),
+        };
+        game.put_stone(p.x, p.y)
+            .expect("the computer should play a legal move");
+    }
+
+    game.board().count(Stone::Black) as i32 - game.board().count(Stone::White) as i32
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::computer::{SimpleComputer, WeightedComputer};
+
+    use super::*;
+
+    fn result(wins: usize, draws: usize, losses: usize) -> MatchResult {
+        MatchResult {
+            wins,
+            draws,
+            losses,
+            discs: 0,
+        }
+    }
+
+    #[test]
+    fn openings_are_legal() {
+        for opening in DEFAULT_OPENINGS {
+            assert!(SimpleReversiGame::from_transcript(opening).is_ok(), "{}", opening);
+        }
+    }
+
+    #[test]
+    fn elo() {
+        let (elo, margin) = result(50, 0, 50).elo();
+        assert_eq!(elo, 0.0);
+        assert!((margin - 69.0).abs() < 0.5, "{}", margin);
+
+        // 75% is about +191.
+        let (elo, _) = result(70, 10, 20).elo();
+        assert!((elo - 190.8).abs() < 0.1, "{}", elo);
+
+        let (_, wide) = result(30, 0, 10).elo();
+        let (_, narrow) = result(300, 0, 100).elo();
+        assert!(narrow < wide);
+        assert_eq!(result(10, 0, 0).elo(), (f64::INFINITY, f64::INFINITY));
+        assert_eq!(result(0, 0, 10).elo(), (f64::NEG_INFINITY, f64::INFINITY));
+    }
+
+    #[test]
+    fn sprt() {
+        let sprt = Sprt::new(0.0, 20.0);
+
+        assert_eq!(sprt.test(&result(5, 0, 5)), SprtDecision::Continue);
+        assert_eq!(sprt.test(&result(400, 0, 200)), SprtDecision::AcceptH1);
+        assert_eq!(sprt.test(&result(200, 0, 400)), SprtDecision::AcceptH0);
+        assert!(sprt.llr(&result(60, 0, 40)) > sprt.llr(&result(50, 0, 50)));
+    }
+
+    #[test]
+    fn round_robin() {
+        let mut tournament = Tournament::new();
+        tournament.add("weighted", |color| Box::new(WeightedComputer::new(color)));
+        tournament.add("simple", |color| Box::new(SimpleComputer::new(color)));
+        tournament.add("weighted 2", |color| Box::new(WeightedComputer::new(color)));
+        tournament.set_games(4);
+
+        let report = tournament.run();
+        assert_eq!(report.pairings.len(), 3);
+        for pairing in &report.pairings {
+            assert_eq!(pairing.result.games(), 4);
+        }
+
+        // The same computer with each color from the same openings.
+        let mirror = &report.pairings[1];
+        assert_eq!((mirror.first, mirror.second), (0, 2));
+        assert_eq!(mirror.result.wins, mirror.result.losses);
+        assert_eq!(mirror.result.discs, 0);
+
+        assert_eq!(report.total(1).games(), 8);
+        let mut total = report.pairings[0].result.reversed();
+        total.add(&report.pairings[2].result);
+        assert_eq!(report.total(1), total);
+        assert!(report.to_string().contains("weighted vs simple: +"));
+    }
+
+    #[test]
+    fn gauntlet_with_sprt() {
+        let mut tournament = Tournament::new();
+        tournament.add("weighted", |color| Box::new(WeightedComputer::new(color)));
+        tournament.add("simple", |color| Box::new(SimpleComputer::new(color)));
+        tournament.add("simple 2", |color| Box::new(SimpleComputer::new(color)));
+        tournament.set_format(Format::Gauntlet);
+        tournament.set_games(200);
+
+        // Any decision at all ends the match long before 200 games.
+        tournament.set_sprt(Sprt {
+            elo0: 0.0,
+            elo1: 400.0,
+            alpha: 0.2,
+            beta: 0.2,
+        });
+
+        let report = tournament.run();
+        assert_eq!(report.pairings.len(), 2);
+        for pairing in &report.pairings {
+            assert_eq!(pairing.first, 0);
+            assert!(pairing.decision.is_some());
+            assert!(pairing.result.games() < 200);
+        }
+    }
+}
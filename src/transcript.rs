@@ -0,0 +1,108 @@
+// Compact transcripts such as "f5d6c3d3c4", as exchanged between Othello programs.
+
+use crate::{
+    error::{ReplayError, ReplayErrorKind},
+    game::Move,
+    point::Action,
+};
+
+/// Splits a transcript into moves. Separators (whitespace, commas, dots) are
+/// ignored, and passes may be written as "pass", "pa" or "--".
+pub fn parse(transcript: &str, size: usize) -> Result<Vec<Action>, ReplayError> {
+    let mut result: Vec<Action> = Vec::new();
+    let mut rest = transcript;
+
+    loop {
+        rest = rest.trim_start_matches(|c: char| c.is_whitespace() || c == ',' || c == '.');
+        if rest.is_empty() {
+            break;
+        }
+
+        let ply = result.len() + 1;
+        let token = next_token(rest);
+        rest = &rest[token.len()..];
+
+        let action = Action::parse_on(token, size)
+            .map_err(|e| ReplayError::new(ply, ReplayErrorKind::Parse(e)))?;
+        result.push(action);
+    }
+
+    Ok(result)
+}
+
+fn next_token(s: &str) -> &str {
+    let lower = s.to_ascii_lowercase();
+
+    if lower.starts_with(Action::PASS) {
+        return &s[..Action::PASS.len()];
+    }
+
+    if lower.starts_with("--") {
+        return &s[..2];
+    }
+
+    if lower.starts_with("pa") && !lower[2..].starts_with(|c: char| c.is_ascii_digit()) {
+        return &s[..2];
+    }
+
+    let column = s.chars().next().map_or(0, char::len_utf8);
+    let row = s[column..]
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(s.len() - column);
+
+    &s[..column + row]
+}
+
+/// Writes the stones put in `moves` without separators. Passes are implied.
+pub fn format<'a>(moves: impl IntoIterator<Item = &'a Move>) -> String {
+    moves
+        .into_iter()
+        .filter_map(|m| match m {
+            Move::Put { point, .. } => Some(point.to_string()),
+            Move::Pass(_) => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{error::ParsePointError, point::Point};
+
+    use super::*;
+
+    #[test]
+    fn parse_transcript() {
+        assert_eq!(
+            parse("f5d6C3", 8),
+            Ok(vec![
+                Action::Put(Point::new(5, 4)),
+                Action::Put(Point::new(3, 5)),
+                Action::Put(Point::new(2, 2)),
+            ])
+        );
+        assert_eq!(
+            parse("f5 d6, pass --PAc3", 8).unwrap()[1..],
+            [
+                Action::Put(Point::new(3, 5)),
+                Action::Pass,
+                Action::Pass,
+                Action::Pass,
+                Action::Put(Point::new(2, 2)),
+            ]
+        );
+        assert_eq!(
+            parse("f5d6i3", 8),
+            Err(ReplayError::new(
+                3,
+                ReplayErrorKind::Parse(ParsePointError::OutOfBoard)
+            ))
+        );
+        assert_eq!(
+            parse("f5d0", 8),
+            Err(ReplayError::new(
+                2,
+                ReplayErrorKind::Parse(ParsePointError::InvalidRow)
+            ))
+        );
+    }
+}
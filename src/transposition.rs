@@ -0,0 +1,162 @@
+// A fixed-size transposition table, shared between threads through an `Arc`.
+// Keys are stored XORed with the entry, so torn writes don't match on probe.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::point::Point;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bound {
+    Exact,
+    /// The score is at least `score`.
+    Lower,
+    /// The score is at most `score`.
+    Upper,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Entry {
+    pub depth: u8,
+    pub score: i32,
+    pub bound: Bound,
+    pub best_move: Option<Point>,
+}
+
+impl Entry {
+    fn pack(&self) -> u64 {
+        let bound = match self.bound {
+            Bound::Exact => 1,
+            Bound::Lower => 2,
+            Bound::Upper => 3,
+        };
+        let (x, y) = match self.best_move {
+            Some(Point { x, y }) => (x as u64 + 1, y as u64 + 1),
+            None => (0, 0),
+        };
+
+        (self.score as u32 as u64) | (self.depth as u64) << 32 | bound << 40 | x << 42 | y << 50
+    }
+
+    fn unpack(data: u64) -> Option<Self> {
+        let bound = match (data >> 40) & 0b11 {
+            1 => Bound::Exact,
+            2 => Bound::Lower,
+            3 => Bound::Upper,
+            _ => return None,
+        };
+        let (x, y) = ((data >> 42) & 0xFF, (data >> 50) & 0xFF);
+        let best_move = (x != 0).then(|| Point::new(x as usize - 1, y as usize - 1));
+
+        Some(Self {
+            depth: (data >> 32) as u8,
+            score: data as u32 as i32,
+            bound,
+            best_move,
+        })
+    }
+}
+
+struct Slot {
+    check: AtomicU64,
+    data: AtomicU64,
+}
+
+pub struct TranspositionTable {
+    slots: Vec<Slot>,
+}
+
+impl TranspositionTable {
+    /// Creates a table with at least `capacity` entries, rounded up to a power of two.
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1).next_power_of_two();
+        let slots = (0..capacity)
+            .map(|_| Slot {
+                check: AtomicU64::new(0),
+                data: AtomicU64::new(0),
+            })
+            .collect();
+
+        Self { slots }
+    }
+
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
+    #[inline]
+    fn slot(&self, key: u64) -> &Slot {
+        &self.slots[key as usize & (self.slots.len() - 1)]
+    }
+
+    pub fn probe(&self, key: u64) -> Option<Entry> {
+        let slot = self.slot(key);
+        let data = slot.data.load(Ordering::Relaxed);
+        let check = slot.check.load(Ordering::Relaxed);
+
+        if check ^ data != key {
+            return None;
+        }
+
+        Entry::unpack(data)
+    }
+
+    /// Stores `entry`, unless the slot holds a deeper search of the same position.
+    pub fn store(&self, key: u64, entry: Entry) {
+        if let Some(existing) = self.probe(key) {
+            if existing.depth > entry.depth {
+                return;
+            }
+        }
+
+        let data = entry.pack();
+        let slot = self.slot(key);
+        slot.data.store(data, Ordering::Relaxed);
+        slot.check.store(key ^ data, Ordering::Relaxed);
+    }
+
+    pub fn clear(&self) {
+        for slot in &self.slots {
+            slot.data.store(0, Ordering::Relaxed);
+            slot.check.store(0, Ordering::Relaxed);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn store_and_probe() {
+        let table = TranspositionTable::new(1000);
+        assert_eq!(table.capacity(), 1024);
+
+        let entry = Entry {
+            depth: 5,
+            score: -1_000_012,
+            bound: Bound::Upper,
+            best_move: Some(Point::new(7, 0)),
+        };
+        table.store(42, entry);
+
+        assert_eq!(table.probe(42), Some(entry));
+        assert_eq!(table.probe(42 + 1024), None);
+        assert_eq!(table.probe(43), None);
+
+        let shallow = Entry {
+            depth: 2,
+            best_move: None,
+            ..entry
+        };
+        table.store(42, shallow);
+        assert_eq!(table.probe(42), Some(entry));
+
+        table.store(42 + 1024, shallow);
+        assert_eq!(table.probe(42), None);
+        assert_eq!(table.probe(42 + 1024), Some(shallow));
+
+        table.clear();
+        assert_eq!(table.probe(42 + 1024), None);
+    }
+}
@@ -0,0 +1,162 @@
+// Zobrist hashing, with keys derived from the square and color.
+
+use crate::{board::ReversiBoard, point::Point, stone::Stone, symmetry::Symmetry};
+
+const SEED: u64 = 0x5EED_0F2E_7E25_B1C3;
+
+// SplitMix64 finalizer.
+const fn mix(mut z: u64) -> u64 {
+    z = z.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+const WHITE_TO_MOVE: u64 = mix(SEED);
+
+#[inline]
+pub fn square_key(x: usize, y: usize, stone: Stone) -> u64 {
+    let color = match stone {
+        Stone::Black => 0,
+        Stone::White => 1,
+    };
+
+    mix(SEED ^ ((y as u64) << 33 | (x as u64) << 1 | color))
+}
+
+/// The change in hash when the stone at (x, y) is flipped.
+#[inline]
+pub fn flip_key(x: usize, y: usize) -> u64 {
+    square_key(x, y, Stone::Black) ^ square_key(x, y, Stone::White)
+}
+
+/// A key for an 8x8 position given as the masks of the player to move and of
+/// the opponent, as in `BitBoard`. It can only be compared with other such
+/// keys.
+#[inline]
+pub fn mask_key(own: u64, opponent: u64) -> u64 {
+    mix(own ^ SEED) ^ mix(opponent ^ WHITE_TO_MOVE)
+}
+
+#[inline]
+pub fn turn_key(turn: Stone) -> u64 {
+    match turn {
+        Stone::Black => 0,
+        Stone::White => WHITE_TO_MOVE,
+    }
+}
+
+/// Hashes the stones on `board` from scratch.
+pub fn hash(board: &dyn ReversiBoard) -> u64 {
+    let mut result = 0;
+
+    for y in 0..board.size() {
+        for x in 0..board.size() {
+            if let Some(stone) = board.get_at(x, y) {
+                result ^= square_key(x, y, stone);
+            }
+        }
+    }
+
+    result
+}
+
+/// Like `position_key`, but the same for all the symmetric images of the
+/// position. Slower, as it hashes the board in all 8 orientations.
+pub fn canonical_key(board: &dyn ReversiBoard, turn: Stone) -> u64 {
+    let size = board.size();
+
+    let hashes = Symmetry::ALL.map(|symmetry| {
+        let mut result = 0;
+        for y in 0..size {
+            for x in 0..size {
+                if let Some(stone) = board.get_at(x, y) {
+                    let p = symmetry.apply(Point::new(x, y), size);
+                    result ^= square_key(p.x, p.y, stone);
+                }
+            }
+        }
+        result
+    });
+
+    hashes.into_iter().min().unwrap() ^ turn_key(turn)
+}
+
+/// Hash of the stones and the player to move, for telling positions apart.
+#[inline]
+pub fn position_key(board: &dyn ReversiBoard, turn: Stone) -> u64 {
+    board.hash() ^ turn_key(turn)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{bitboard::BitBoard, board::ArrayBasedBoard, game::SimpleReversiGame};
+
+    use super::*;
+
+    #[test]
+    fn incremental_hash() {
+        let mut array = ArrayBasedBoard::new();
+        let mut bits = BitBoard::new();
+        array.init_four_central_squares();
+        bits.init_four_central_squares();
+        assert_eq!(array.hash(), hash(&array));
+        assert_eq!(array.hash(), bits.hash());
+
+        for (x, y, player) in [(3, 2, Stone::Black), (2, 2, Stone::White), (2, 3, Stone::Black)] {
+            array.put_stone(x, y, player).unwrap();
+            bits.put_stone(x, y, player).unwrap();
+            assert_eq!(array.hash(), hash(&array));
+            assert_eq!(bits.hash(), hash(&bits));
+            assert_eq!(array.hash(), bits.hash());
+        }
+
+        array.board_mut()[0][0] = Some(Stone::White);
+        bits.board_mut()[0][0] = Some(Stone::White);
+        assert_eq!(array.hash(), hash(&array));
+        assert_eq!(bits.hash(), hash(&bits));
+
+        array.flip(0, 0).unwrap();
+        array.set_at(7, 7, Some(Stone::Black)).unwrap();
+        assert_eq!(array.hash(), hash(&array));
+    }
+
+    #[test]
+    fn transpositions() {
+        let a = SimpleReversiGame::from_transcript("f5f6e6").unwrap();
+        let b = SimpleReversiGame::from_transcript("e6f6f5").unwrap();
+        let c = SimpleReversiGame::from_transcript("f5f6d3").unwrap();
+
+        assert_eq!(a.position(), b.position());
+        assert_eq!(
+            position_key(a.board(), a.turn()),
+            position_key(b.board(), b.turn())
+        );
+        assert_ne!(a.position(), c.position());
+        assert_ne!(
+            position_key(a.board(), a.turn()),
+            position_key(c.board(), c.turn())
+        );
+        assert_ne!(
+            position_key(a.board(), Stone::Black),
+            position_key(a.board(), Stone::White)
+        );
+    }
+
+    #[test]
+    fn symmetric_positions() {
+        let a = SimpleReversiGame::from_transcript("f5d6").unwrap();
+        let b = SimpleReversiGame::from_transcript("c4e3").unwrap();
+        let c = SimpleReversiGame::from_transcript("f5f6").unwrap();
+
+        assert_ne!(a.position(), b.position());
+        assert_eq!(
+            canonical_key(a.board(), a.turn()),
+            canonical_key(b.board(), b.turn())
+        );
+        assert_ne!(
+            canonical_key(a.board(), a.turn()),
+            canonical_key(c.board(), c.turn())
+        );
+    }
+}